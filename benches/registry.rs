@@ -0,0 +1,107 @@
+//! Per-poll cost of aircraft DB enrichment for 500 aircraft.
+//!
+//! Run with `cargo bench --bench registry`. Compares the old approach
+//! (open the DB and query row by row on every poll) with
+//! `AircraftRegistry` on a cold and a warm cache.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use redwood_tui::db::{migrations, AircraftRegistry};
+use redwood_tui::models::Flight;
+use rusqlite::{params, Connection, OpenFlags};
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Rows in the fixture DB.
+const DB_ROWS: u32 = 50_000;
+/// Aircraft per poll; one in five is not in the DB.
+const POLL_SIZE: u32 = 500;
+
+/// A temp dir holding `aircraft.db` at the current schema with
+/// [`DB_ROWS`] aircraft.
+fn fixture() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let mut conn = Connection::open(dir.path().join("aircraft.db")).unwrap();
+    migrations::migrate(&mut conn).unwrap();
+    let tx = conn.transaction().unwrap();
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO aircraft (icao24, manufacturerName, model, operator, operatorCallsign, registration, typecode)
+                VALUES (?, 'Boeing', '737-800', 'Operator', 'OPR', ?, 'B738')",
+            )
+            .unwrap();
+        for i in 0..DB_ROWS {
+            stmt.execute(params![format!("{:06x}", i * 7), format!("N{}", i)])
+                .unwrap();
+        }
+    }
+    tx.commit().unwrap();
+    dir
+}
+
+fn poll_flights() -> Vec<Flight> {
+    (0..POLL_SIZE)
+        .map(|i| Flight {
+            icao24: if i % 5 == 0 {
+                format!("{:06x}", 0xf00000 + i)
+            } else {
+                format!("{:06x}", i * 7 * 13)
+            },
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// The pre-registry enrichment: a fresh connection and one query per flight.
+fn reopen_per_poll(path: &Path, flights: &mut [Flight]) {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap();
+    let mut stmt = conn
+        .prepare("SELECT registration, typecode FROM aircraft WHERE icao24 = ?")
+        .unwrap();
+    for flight in flights {
+        if let Ok((reg, ty)) = stmt.query_row([&flight.icao24], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        }) {
+            flight.registration = Some(reg);
+            flight.aircraft_type = Some(ty);
+        }
+    }
+}
+
+fn bench_registry(c: &mut Criterion) {
+    let dir = fixture();
+    let path = dir.path().join("aircraft.db");
+    let flights = poll_flights();
+    let mut group = c.benchmark_group("enrich_500_aircraft");
+
+    group.bench_function("reopen_per_poll", |b| {
+        b.iter_batched(
+            || flights.clone(),
+            |mut flights| reopen_per_poll(&path, &mut flights),
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("registry_cold_cache", |b| {
+        b.iter_batched(
+            || (AircraftRegistry::open(&path).unwrap(), flights.clone()),
+            |(mut registry, mut flights)| registry.decorate(&mut flights).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let mut warm = AircraftRegistry::open(&path).unwrap();
+    warm.decorate(&mut flights.clone()).unwrap();
+    group.bench_function("registry_warm_cache", |b| {
+        b.iter_batched(
+            || flights.clone(),
+            |mut flights| warm.decorate(&mut flights).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_registry);
+criterion_main!(benches);
@@ -0,0 +1,238 @@
+//! Emergency squawk and ident alerting for the Redwood flight tracker.
+//!
+//! After every flight update [`AlertLog::update`] compares each aircraft's
+//! transponder state with the alerts already open. An aircraft squawking
+//! 7500, 7600 or 7700, or holding the SPI ("ident") flag, raises an alert
+//! that stays active until the condition clears or the aircraft leaves the
+//! feed. Military and government aircraft can optionally raise one too.
+//! Closed alerts are kept in a bounded history so a missed alert can be
+//! reviewed later.
+
+use crate::classify::AircraftClass;
+use crate::models::Flight;
+use std::collections::{HashMap, VecDeque};
+
+/// Closed alerts kept for the history panel.
+const HISTORY_LEN: usize = 100;
+
+/// What raised an alert, most severe first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AlertKind {
+    /// Squawk 7500: unlawful interference.
+    Hijack,
+    /// Squawk 7700: general emergency.
+    Emergency,
+    /// Squawk 7600: radio failure.
+    RadioFailure,
+    /// Special position identification pulse ("ident").
+    Ident,
+    /// Military aircraft in range (opt-in).
+    Military,
+    /// Government aircraft in range (opt-in).
+    Government,
+}
+
+impl AlertKind {
+    /// Alert for an emergency squawk code, if it is one.
+    pub fn from_squawk(squawk: &str) -> Option<Self> {
+        match squawk.trim() {
+            "7500" => Some(AlertKind::Hijack),
+            "7700" => Some(AlertKind::Emergency),
+            "7600" => Some(AlertKind::RadioFailure),
+            _ => None,
+        }
+    }
+
+    /// Short label for the UI, e.g. `"7700 EMERGENCY"`.
+    pub fn label(self) -> &'static str {
+        match self {
+            AlertKind::Hijack => "7500 HIJACK",
+            AlertKind::Emergency => "7700 EMERGENCY",
+            AlertKind::RadioFailure => "7600 RADIO FAIL",
+            AlertKind::Ident => "IDENT",
+            AlertKind::Military => "MILITARY",
+            AlertKind::Government => "GOVERNMENT",
+        }
+    }
+
+    /// Alerts `flight` currently warrants; `military` enables the
+    /// military/government alerts.
+    pub fn of(flight: &Flight, military: bool) -> Vec<Self> {
+        let mut kinds: Vec<Self> = flight
+            .squawk
+            .as_deref()
+            .and_then(Self::from_squawk)
+            .into_iter()
+            .collect();
+        if flight.spi {
+            kinds.push(AlertKind::Ident);
+        }
+        if military {
+            match AircraftClass::of(flight) {
+                AircraftClass::Military => kinds.push(AlertKind::Military),
+                AircraftClass::Government => kinds.push(AlertKind::Government),
+                AircraftClass::Civil => {}
+            }
+        }
+        kinds
+    }
+}
+
+/// One raised alert.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub icao24: String,
+    pub callsign: String,
+    pub kind: AlertKind,
+    /// Unix time (seconds) the alert was raised.
+    pub raised_at: i64,
+    /// Unix time (seconds) the condition cleared; `None` while active.
+    pub cleared_at: Option<i64>,
+}
+
+/// Open alerts plus a bounded history of closed ones.
+#[derive(Debug, Default)]
+pub struct AlertLog {
+    active: HashMap<(String, AlertKind), Alert>,
+    history: VecDeque<Alert>,
+}
+
+impl AlertLog {
+    /// Opens alerts for new conditions and closes those that cleared.
+    /// `military` enables the military/government alerts. Returns the
+    /// alerts raised by this update.
+    pub fn update(&mut self, flights: &[Flight], now: i64, military: bool) -> Vec<Alert> {
+        let mut current: HashMap<(String, AlertKind), &Flight> = HashMap::new();
+        for flight in flights {
+            for kind in AlertKind::of(flight, military) {
+                current.insert((flight.icao24.clone(), kind), flight);
+            }
+        }
+
+        let cleared: Vec<_> = self
+            .active
+            .keys()
+            .filter(|key| !current.contains_key(*key))
+            .cloned()
+            .collect();
+        for key in cleared {
+            if let Some(mut alert) = self.active.remove(&key) {
+                alert.cleared_at = Some(now);
+                self.history.push_front(alert);
+            }
+        }
+        self.history.truncate(HISTORY_LEN);
+
+        let mut raised = Vec::new();
+        for (key, flight) in current {
+            if let Some(alert) = self.active.get_mut(&key) {
+                alert.callsign.clone_from(&flight.callsign);
+                continue;
+            }
+            let alert = Alert {
+                icao24: key.0.clone(),
+                callsign: flight.callsign.clone(),
+                kind: key.1,
+                raised_at: now,
+                cleared_at: None,
+            };
+            raised.push(alert.clone());
+            self.active.insert(key, alert);
+        }
+        raised.sort_by_key(|a| a.kind);
+        raised
+    }
+
+    /// Most severe active alert for an aircraft.
+    pub fn active_for(&self, icao24: &str) -> Option<AlertKind> {
+        self.active
+            .values()
+            .filter(|a| a.icao24 == icao24)
+            .map(|a| a.kind)
+            .min()
+    }
+
+    /// Active alerts, most severe and then newest first.
+    pub fn active(&self) -> Vec<&Alert> {
+        let mut active: Vec<&Alert> = self.active.values().collect();
+        active.sort_by(|a, b| a.kind.cmp(&b.kind).then(b.raised_at.cmp(&a.raised_at)));
+        active
+    }
+
+    /// Closed alerts, most recently cleared first.
+    pub fn history(&self) -> impl Iterator<Item = &Alert> {
+        self.history.iter()
+    }
+
+    /// Whether no alert is open.
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flight(icao: &str, squawk: &str, spi: bool) -> Flight {
+        Flight {
+            icao24: icao.to_string(),
+            callsign: "TEST1".to_string(),
+            squawk: Some(squawk.to_string()),
+            spi,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_emergency_codes() {
+        assert_eq!(AlertKind::from_squawk("7700"), Some(AlertKind::Emergency));
+        assert_eq!(AlertKind::from_squawk("7500"), Some(AlertKind::Hijack));
+        assert_eq!(
+            AlertKind::from_squawk("7600"),
+            Some(AlertKind::RadioFailure)
+        );
+        assert_eq!(AlertKind::from_squawk("1200"), None);
+        assert_eq!(
+            AlertKind::of(&flight("a", "7600", true), false),
+            vec![AlertKind::RadioFailure, AlertKind::Ident]
+        );
+
+        // Military alerts are opt-in.
+        let mil = flight("ae1234", "1200", false);
+        assert!(AlertKind::of(&mil, false).is_empty());
+        assert_eq!(AlertKind::of(&mil, true), vec![AlertKind::Military]);
+    }
+
+    #[test]
+    fn test_alerts_raise_once_and_clear_to_history() {
+        let mut log = AlertLog::default();
+        let raised = log.update(
+            &[
+                flight("aaaaaa", "7700", false),
+                flight("bbbbbb", "1200", false),
+            ],
+            10,
+            false,
+        );
+        assert_eq!(raised.len(), 1);
+        assert_eq!(raised[0].kind, AlertKind::Emergency);
+        assert_eq!(log.active_for("aaaaaa"), Some(AlertKind::Emergency));
+        assert_eq!(log.active_for("bbbbbb"), None);
+
+        // Still squawking: nothing new.
+        assert!(log
+            .update(&[flight("aaaaaa", "7700", false)], 20, false)
+            .is_empty());
+
+        // Back to a normal code: the alert moves to history.
+        log.update(&[flight("aaaaaa", "2000", false)], 30, false);
+        assert!(log.is_empty());
+        let history: Vec<_> = log.history().collect();
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            (history[0].raised_at, history[0].cleared_at),
+            (10, Some(30))
+        );
+    }
+}
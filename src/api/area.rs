@@ -0,0 +1,168 @@
+//! Geodesic search area.
+//!
+//! A [`SearchArea`] is a circle on the Earth's surface. Sources that can only
+//! be queried by latitude/longitude box (OpenSky) ask for
+//! [`SearchArea::bounding_boxes`] — the box widens in longitude by
+//! `1/cos(lat)` away from the equator, covers every longitude once it reaches
+//! a pole, and is split in two where it crosses the ±180° meridian — and
+//! then drop the box corners with [`SearchArea::contains`].
+
+use crate::models::Flight;
+
+/// Mean Earth radius in kilometres, as used by [`Flight::distance_from`].
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Circular area around the observer that sources are asked to cover.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchArea {
+    /// Centre latitude in decimal degrees.
+    pub lat: f64,
+    /// Centre longitude in decimal degrees.
+    pub lon: f64,
+    /// Radius in kilometres.
+    pub radius_km: f64,
+}
+
+/// Latitude/longitude box in decimal degrees, with `lomin <= lomax`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub lamin: f64,
+    pub lomin: f64,
+    pub lamax: f64,
+    pub lomax: f64,
+}
+
+impl SearchArea {
+    /// Boxes that together cover the circle: one normally, two when it
+    /// crosses the antimeridian.
+    pub fn bounding_boxes(&self) -> Vec<BoundingBox> {
+        let lat_pad = (self.radius_km / EARTH_RADIUS_KM).to_degrees();
+        let lamin = (self.lat - lat_pad).max(-90.0);
+        let lamax = (self.lat + lat_pad).min(90.0);
+
+        // Meridians converge towards the poles, so a degree of longitude is
+        // shortest at the box edge farthest from the equator.
+        let widest_lat = lamin.abs().max(lamax.abs());
+        let cos_lat = widest_lat.to_radians().cos();
+        let lon_pad = if lamin <= -90.0 || lamax >= 90.0 || cos_lat <= 0.0 {
+            180.0
+        } else {
+            lat_pad / cos_lat
+        };
+        if lon_pad >= 180.0 {
+            return vec![BoundingBox {
+                lamin,
+                lomin: -180.0,
+                lamax,
+                lomax: 180.0,
+            }];
+        }
+
+        let lon = normalize_lon(self.lon);
+        let (lomin, lomax) = (lon - lon_pad, lon + lon_pad);
+        let boxed = |lomin: f64, lomax: f64| BoundingBox {
+            lamin,
+            lomin,
+            lamax,
+            lomax,
+        };
+        if lomin < -180.0 {
+            vec![boxed(lomin + 360.0, 180.0), boxed(-180.0, lomax)]
+        } else if lomax > 180.0 {
+            vec![boxed(lomin, 180.0), boxed(-180.0, lomax - 360.0)]
+        } else {
+            vec![boxed(lomin, lomax)]
+        }
+    }
+
+    /// Whether `flight` lies within the circle (great-circle distance).
+    pub fn contains(&self, flight: &Flight) -> bool {
+        flight.distance_from(self.lat, self.lon) <= self.radius_km
+    }
+}
+
+/// Wraps a longitude into `[-180, 180)`.
+fn normalize_lon(lon: f64) -> f64 {
+    (lon + 180.0).rem_euclid(360.0) - 180.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flight_at(lat: f64, lon: f64) -> Flight {
+        Flight {
+            latitude: lat,
+            longitude: lon,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_longitude_widens_with_latitude() {
+        let equator = SearchArea {
+            lat: 0.0,
+            lon: 10.0,
+            radius_km: 100.0,
+        }
+        .bounding_boxes();
+        let north = SearchArea {
+            lat: 70.0,
+            lon: 10.0,
+            radius_km: 100.0,
+        }
+        .bounding_boxes();
+
+        let width = |b: &BoundingBox| b.lomax - b.lomin;
+        assert_eq!(equator.len(), 1);
+        assert!((width(&equator[0]) - 1.8).abs() < 0.01);
+        // 1/cos(70.9°) ≈ 3.06 times wider.
+        assert!(width(&north[0]) > 3.0 * width(&equator[0]));
+
+        // A point due east on the circle edge is inside the box.
+        let east = flight_at(70.0, 10.0 + 2.6);
+        assert!(north[0].lomax > east.longitude);
+    }
+
+    #[test]
+    fn test_antimeridian_split() {
+        let fiji = SearchArea {
+            lat: -17.0,
+            lon: 179.5,
+            radius_km: 150.0,
+        };
+        let boxes = fiji.bounding_boxes();
+        assert_eq!(boxes.len(), 2);
+        assert_eq!(boxes[0].lomax, 180.0);
+        assert_eq!(boxes[1].lomin, -180.0);
+        assert!(boxes[1].lomax > -180.0 && boxes[1].lomax < -178.0);
+
+        // Aircraft just across the meridian are in range.
+        assert!(fiji.contains(&flight_at(-17.0, -179.5)));
+    }
+
+    #[test]
+    fn test_polar_area_covers_all_longitudes() {
+        let boxes = SearchArea {
+            lat: 89.5,
+            lon: 0.0,
+            radius_km: 100.0,
+        }
+        .bounding_boxes();
+        assert_eq!(boxes.len(), 1);
+        assert_eq!((boxes[0].lomin, boxes[0].lomax), (-180.0, 180.0));
+        assert_eq!(boxes[0].lamax, 90.0);
+    }
+
+    #[test]
+    fn test_box_corners_are_outside_circle() {
+        let area = SearchArea {
+            lat: 37.77,
+            lon: -122.42,
+            radius_km: 50.0,
+        };
+        let b = area.bounding_boxes()[0];
+        assert!(!area.contains(&flight_at(b.lamax - 0.01, b.lomax - 0.01)));
+        assert!(area.contains(&flight_at(37.9, -122.3)));
+    }
+}
@@ -0,0 +1,238 @@
+//! AVR raw hex frame format (port 30002, `rtl_adsb` output).
+//!
+//! Each line is one Mode-S frame in hex, terminated by `;`:
+//! - `*8D4840D6202CC371C32CE0576098;` — plain AVR.
+//! - `@0000A1B2C3D48D4840D6202CC371C32CE0576098;` — AVR with a 12-hex-digit
+//!   (48-bit, 12 MHz) MLAT timestamp before the frame.
+//!
+//! [`AvrDecoder`] splits the stream into lines and hands each frame to
+//! [`modes::decode`]. The same decoder serves TCP feeds, capture files and
+//! stdin via [`StreamInput`](super::stream::StreamInput). Replayed captures
+//! are timed by their MLAT timestamps (see [`ReplayClock`]).
+
+use super::modes;
+use super::state::AircraftTable;
+use super::stream::StreamDecoder;
+
+/// One parsed AVR line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvrFrame {
+    /// 48-bit MLAT timestamp, present for `@` lines.
+    pub timestamp: Option<u64>,
+    /// Raw Mode-S frame (7 or 14 bytes).
+    pub data: Vec<u8>,
+}
+
+/// Parses one AVR line. Returns `None` for blank lines, comments, unknown
+/// prefixes and frames that are not 56 or 112 bits long.
+pub fn parse_line(line: &str) -> Option<AvrFrame> {
+    let line = line.trim();
+    let body = line.strip_suffix(';').unwrap_or(line);
+
+    let (timestamp, hex) = match body.chars().next()? {
+        '*' => (None, &body[1..]),
+        '@' => {
+            let ts = body.get(1..13)?;
+            (Some(u64::from_str_radix(ts, 16).ok()?), &body[13..])
+        }
+        _ => return None,
+    };
+
+    let data = modes::from_hex(hex)?;
+    if data.len() != 7 && data.len() != 14 {
+        return None;
+    }
+    Some(AvrFrame { timestamp, data })
+}
+
+/// Ticks per second of the AVR MLAT timestamp.
+const MLAT_HZ: u64 = 12_000_000;
+/// The MLAT timestamp is a 48-bit counter.
+const MLAT_MASK: u64 = (1 << 48) - 1;
+
+/// Times replayed frames by their MLAT timestamps: the first timestamped
+/// frame is stamped with the wall-clock time the replay reached it, and
+/// every later one that many seconds after it. Frames without a timestamp
+/// share the time of the last one that had one.
+///
+/// A frame is never stamped later than the time it is read. When a replay
+/// runs ahead of real time the clock is re-based onto the read time, so a
+/// capture read faster than it was recorded is compressed rather than
+/// pushed into the future, where it would outrank live sources.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReplayClock {
+    /// MLAT counter and unix time of the first timestamped frame.
+    origin: Option<(u64, i64)>,
+    last: Option<i64>,
+}
+
+impl ReplayClock {
+    /// Unix time (seconds) of a frame with `timestamp`, read at `now`.
+    pub fn time(&mut self, timestamp: Option<u64>, now: i64) -> i64 {
+        let Some(timestamp) = timestamp else {
+            return self.last.unwrap_or(now);
+        };
+        let (first, start) = self.origin.get_or_insert((timestamp, now));
+        let ticks = timestamp.wrapping_sub(*first) & MLAT_MASK;
+        let mut time = *start + (ticks / MLAT_HZ) as i64;
+        if time > now {
+            *start -= time - now;
+            time = now;
+        }
+        self.last = Some(time);
+        time
+    }
+}
+
+/// Line-buffered AVR stream decoder.
+#[derive(Debug, Clone, Default)]
+pub struct AvrDecoder {
+    pending: Vec<u8>,
+    /// Set for replays; live feeds are stamped as they arrive.
+    replay: Option<ReplayClock>,
+}
+
+impl StreamDecoder for AvrDecoder {
+    fn feed(&mut self, bytes: &[u8], table: &mut AircraftTable, now: i64) {
+        self.pending.extend_from_slice(bytes);
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            let Some(frame) = parse_line(&String::from_utf8_lossy(&line)) else {
+                continue;
+            };
+            let time = match &mut self.replay {
+                Some(clock) => clock.time(frame.timestamp, now),
+                None => now,
+            };
+            if let Some(msg) = modes::decode(&frame.data) {
+                msg.apply(table, time);
+            }
+        }
+    }
+
+    fn start_replay(&mut self) {
+        self.replay = Some(ReplayClock::default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::stream::{StreamInput, StreamSource};
+    use crate::api::{FlightSource, SearchArea};
+    use std::time::Duration;
+
+    const CAPTURE: &str = "\
+*8D4840D6202CC371C32CE0576098;
+@00001A2B3C4D8D40621D58C386435CC412692AD6;
+*8d40621d58c382d690c8ac2863a7;
+garbage line
+*8D485020994409940838175B284F;
+";
+
+    #[test]
+    fn test_parse_plain_and_mlat_lines() {
+        let plain = parse_line("*8D4840D6202CC371C32CE0576098;").unwrap();
+        assert_eq!(plain.timestamp, None);
+        assert_eq!(plain.data.len(), 14);
+
+        let mlat = parse_line("@00001A2B3C4D8D40621D58C386435CC412692AD6;").unwrap();
+        assert_eq!(mlat.timestamp, Some(0x1A2B3C4D));
+        assert_eq!(mlat.data[0], 0x8D);
+
+        assert!(parse_line("").is_none());
+        assert!(parse_line("*8D48;").is_none());
+        assert!(parse_line("*ZZ4840D6202CC371C32CE0576098;").is_none());
+    }
+
+    #[test]
+    fn test_replay_keeps_capture_timing() {
+        // An odd/even pair two seconds apart, then the odd frame again half
+        // an hour later: too old to pair, so it only counts as contact.
+        let capture = "\
+@0000000000008D40621D58C386435CC412692AD6;
+@0000016E36008D40621D58C382D690C8AC2863A7;
+@00050775D8008D40621D58C386435CC412692AD6;
+";
+        let start = 1_700_000_000;
+        let mut decoder = AvrDecoder::default();
+        decoder.start_replay();
+        let mut table = AircraftTable::without_expiry();
+        // Piped in as recorded (e.g. from `rtl_adsb`), each line read a
+        // little after its MLAT time.
+        for (line, read_at) in capture.lines().zip([0, 3, 1801]) {
+            decoder.feed(
+                format!("{}\n", line).as_bytes(),
+                &mut table,
+                start + read_at,
+            );
+        }
+
+        let flight = &table.get("40621d").unwrap().flight;
+        assert_eq!(flight.time_position, Some(start + 2));
+        assert_eq!(flight.last_contact, Some(start + 1800));
+        assert!((flight.latitude - 52.2572).abs() < 1e-4);
+
+        // A live feed stamps everything with the arrival time.
+        let mut live = AvrDecoder::default();
+        let mut table = AircraftTable::new();
+        live.feed(capture.as_bytes(), &mut table, start);
+        let flight = &table.get("40621d").unwrap().flight;
+        assert_eq!(flight.time_position, Some(start));
+        assert_eq!(flight.last_contact, Some(start));
+    }
+
+    #[test]
+    fn test_fast_replay_never_runs_ahead() {
+        let now = 1_700_000_000;
+        let mut clock = ReplayClock::default();
+        // Three frames 2 s and half an hour apart, all read within 10 s.
+        let times: Vec<i64> = [(0, now), (24_000_000, now + 1), (21_600_000_000, now + 10)]
+            .iter()
+            .map(|&(ticks, read_at)| {
+                let time = clock.time(Some(ticks), read_at);
+                assert!(time <= read_at, "stamped {} when read at {}", time, read_at);
+                time
+            })
+            .collect();
+        assert_eq!(times, vec![now, now + 1, now + 10]);
+        // Once re-based, the recorded spacing applies again.
+        assert_eq!(
+            clock.time(Some(21_600_000_000 + 60_000_000), now + 20),
+            now + 15
+        );
+        assert_eq!(clock.time(None, now + 20), now + 15);
+    }
+
+    #[tokio::test]
+    async fn test_replay_capture_file() {
+        let path = std::env::temp_dir().join("redwood-test-capture.avr");
+        std::fs::write(&path, CAPTURE).unwrap();
+
+        let source = StreamSource::with_input(
+            "avr",
+            StreamInput::File(path.clone()),
+            Duration::from_millis(100),
+            AvrDecoder::default(),
+        );
+        let area = SearchArea {
+            lat: 52.25,
+            lon: 3.92,
+            radius_km: 50.0,
+        };
+
+        let mut flights = Vec::new();
+        for _ in 0..50 {
+            flights = source.fetch(&area).await.unwrap();
+            if !flights.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(flights.len(), 1);
+        assert_eq!(flights[0].icao24, "40621d");
+        assert!((flights[0].latitude - 52.2572).abs() < 1e-4);
+    }
+}
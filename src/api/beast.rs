@@ -0,0 +1,236 @@
+//! Beast binary protocol (port 30005) framing.
+//!
+//! Each Beast frame is `0x1a`, a type byte (`'1'` Mode-A/C, `'2'` Mode-S
+//! short, `'3'` Mode-S long), a 6-byte 12 MHz MLAT timestamp, a 1-byte
+//! signal level and the raw message. Any `0x1a` inside the frame body is
+//! doubled on the wire. [`BeastDecoder`] undoes the framing and hands Mode-S
+//! payloads to [`modes::decode`].
+
+use super::modes;
+use super::state::AircraftTable;
+use super::stream::StreamDecoder;
+
+const ESCAPE: u8 = 0x1a;
+
+/// Kind of payload carried by a Beast frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BeastFrameKind {
+    /// 2-byte Mode-A/C reply.
+    ModeAc,
+    /// 7-byte (56-bit) Mode-S frame.
+    ModeSShort,
+    /// 14-byte (112-bit) Mode-S frame.
+    ModeSLong,
+}
+
+impl BeastFrameKind {
+    fn from_type_byte(b: u8) -> Option<Self> {
+        match b {
+            b'1' => Some(Self::ModeAc),
+            b'2' => Some(Self::ModeSShort),
+            b'3' => Some(Self::ModeSLong),
+            _ => None,
+        }
+    }
+
+    fn payload_len(self) -> usize {
+        match self {
+            Self::ModeAc => 2,
+            Self::ModeSShort => 7,
+            Self::ModeSLong => 14,
+        }
+    }
+}
+
+/// One unescaped Beast frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeastFrame {
+    pub kind: BeastFrameKind,
+    /// 48-bit MLAT timestamp (12 MHz ticks; receiver-specific epoch).
+    pub timestamp: u64,
+    /// Raw signal level, 0–255.
+    pub signal: u8,
+    /// Message bytes (2, 7 or 14).
+    pub data: Vec<u8>,
+}
+
+impl BeastFrame {
+    /// Signal level in dBFS, as shown by dump1090 (`10·log10((level/255)²)`).
+    pub fn rssi_dbfs(&self) -> f64 {
+        let level = self.signal as f64 / 255.0;
+        10.0 * (level * level).max(1e-10).log10()
+    }
+}
+
+/// Incremental Beast deframer; keeps partial frames between reads.
+#[derive(Debug, Clone, Default)]
+pub struct BeastDecoder {
+    buf: Vec<u8>,
+}
+
+impl BeastDecoder {
+    /// Appends raw bytes from the feed.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Pops the next complete frame, skipping garbage and corrupt frames.
+    /// Returns `None` once the buffer holds no further complete frame.
+    pub fn next_frame(&mut self) -> Option<BeastFrame> {
+        loop {
+            // Resynchronise on the next frame marker.
+            let start = self.buf.iter().position(|&b| b == ESCAPE)?;
+            self.buf.drain(..start);
+
+            let type_byte = *self.buf.get(1)?;
+            let Some(kind) = BeastFrameKind::from_type_byte(type_byte) else {
+                // Not a frame start (e.g. an escaped 0x1a 0x1a pair in
+                // garbage); drop the marker and keep scanning.
+                self.buf.drain(..1);
+                continue;
+            };
+
+            let body_len = 6 + 1 + kind.payload_len();
+            let mut body = Vec::with_capacity(body_len);
+            let mut i = 2;
+            let mut corrupt = false;
+            while body.len() < body_len {
+                let b = *self.buf.get(i)?;
+                if b == ESCAPE {
+                    match *self.buf.get(i + 1)? {
+                        ESCAPE => i += 2,
+                        _ => {
+                            // Unescaped marker: the frame was truncated and a
+                            // new one starts here.
+                            corrupt = true;
+                            break;
+                        }
+                    }
+                } else {
+                    i += 1;
+                }
+                body.push(b);
+            }
+
+            if corrupt {
+                self.buf.drain(..i);
+                continue;
+            }
+            self.buf.drain(..i);
+
+            let timestamp = body[..6].iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+            return Some(BeastFrame {
+                kind,
+                timestamp,
+                signal: body[6],
+                data: body[7..].to_vec(),
+            });
+        }
+    }
+}
+
+impl StreamDecoder for BeastDecoder {
+    fn feed(&mut self, bytes: &[u8], table: &mut AircraftTable, now: i64) {
+        self.push(bytes);
+        while let Some(frame) = self.next_frame() {
+            if frame.kind == BeastFrameKind::ModeAc {
+                continue;
+            }
+            if let Some(msg) = modes::decode(&frame.data) {
+                msg.apply(table, now);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::modes::from_hex;
+
+    /// Wraps `msg` in a Beast frame, escaping any 0x1a bytes.
+    fn frame(kind: u8, timestamp: u64, signal: u8, msg: &[u8]) -> Vec<u8> {
+        let mut body: Vec<u8> = timestamp.to_be_bytes()[2..].to_vec();
+        body.push(signal);
+        body.extend_from_slice(msg);
+
+        let mut out = vec![ESCAPE, kind];
+        for b in body {
+            out.push(b);
+            if b == ESCAPE {
+                out.push(ESCAPE);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_deframe_with_escapes_and_garbage() {
+        let msg = from_hex("8D4840D6202CC371C32CE0576098").unwrap();
+        // Timestamp and signal both contain 0x1a and must be unescaped.
+        let mut stream = vec![0x00, 0xff];
+        stream.extend(frame(b'3', 0x1a_0000_001a, 0x1a, &msg));
+        stream.extend(frame(b'1', 42, 200, &[0x12, 0x34]));
+
+        let mut decoder = BeastDecoder::default();
+        decoder.push(&stream);
+
+        let first = decoder.next_frame().unwrap();
+        assert_eq!(first.kind, BeastFrameKind::ModeSLong);
+        assert_eq!(first.timestamp, 0x1a_0000_001a);
+        assert_eq!(first.signal, 0x1a);
+        assert_eq!(first.data, msg);
+
+        let second = decoder.next_frame().unwrap();
+        assert_eq!(second.kind, BeastFrameKind::ModeAc);
+        assert_eq!(second.data, vec![0x12, 0x34]);
+        assert!(decoder.next_frame().is_none());
+    }
+
+    #[test]
+    fn test_partial_frames_wait_for_more_data() {
+        let msg = from_hex("2A00516D492B80").unwrap();
+        let bytes = frame(b'2', 1, 100, &msg);
+
+        let mut decoder = BeastDecoder::default();
+        decoder.push(&bytes[..5]);
+        assert!(decoder.next_frame().is_none());
+        decoder.push(&bytes[5..]);
+        assert_eq!(decoder.next_frame().unwrap().data, msg);
+    }
+
+    #[test]
+    fn test_truncated_frame_is_dropped() {
+        let msg = from_hex("8D4840D6202CC371C32CE0576098").unwrap();
+        let good = frame(b'3', 7, 50, &msg);
+
+        let mut stream = good[..8].to_vec();
+        stream.extend(&good);
+
+        let mut decoder = BeastDecoder::default();
+        decoder.push(&stream);
+        let f = decoder.next_frame().unwrap();
+        assert_eq!(f.timestamp, 7);
+        assert_eq!(f.data, msg);
+        assert!(decoder.next_frame().is_none());
+    }
+
+    #[test]
+    fn test_feed_populates_table() {
+        let mut stream = Vec::new();
+        for hex in [
+            "8D4840D6202CC371C32CE0576098",
+            "8D40621D58C386435CC412692AD6",
+            "8D40621D58C382D690C8AC2863A7",
+        ] {
+            stream.extend(frame(b'3', 0, 128, &from_hex(hex).unwrap()));
+        }
+
+        let mut table = AircraftTable::new();
+        BeastDecoder::default().feed(&stream, &mut table, 1_700_000_000);
+
+        assert_eq!(table.get("4840d6").unwrap().flight.callsign, "KLM1023");
+        let pos = table.get("40621d").unwrap();
+        assert!((pos.flight.longitude - 3.91937).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,253 @@
+//! dump1090 / readsb / tar1090 `aircraft.json` flight source.
+//!
+//! Local ADS-B decoders publish their current aircraft table as JSON, either
+//! over HTTP (e.g. `http://receiver/tar1090/data/aircraft.json`) or as a file
+//! on disk (e.g. `/run/readsb/aircraft.json`). This source reads that table
+//! and converts each entry from aviation units into a [`Flight`].
+
+use super::{FlightSource, SearchArea, SourceHealth, FEET_TO_METERS, FPM_TO_MPS, KNOTS_TO_MPS};
+use crate::models::Flight;
+use async_trait::async_trait;
+use color_eyre::Result;
+use reqwest::Client;
+use serde::Deserialize;
+use std::sync::Mutex;
+
+/// Top level of an `aircraft.json` document.
+#[derive(Debug, Deserialize)]
+pub struct AircraftJson {
+    /// Unix time (seconds) at which the file was written.
+    pub now: Option<f64>,
+    /// Aircraft currently known to the decoder.
+    #[serde(default)]
+    pub aircraft: Vec<AircraftEntry>,
+}
+
+/// One aircraft entry. Older dump1090 builds use `altitude`, `speed` and
+/// `vert_rate` instead of the readsb field names; both are accepted.
+#[derive(Debug, Deserialize)]
+pub struct AircraftEntry {
+    /// ICAO24 address in hex; prefixed with `~` for non-ICAO (TIS-B) targets.
+    pub hex: String,
+    /// Callsign, space padded.
+    pub flight: Option<String>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    /// Barometric altitude in feet, or the string `"ground"`.
+    #[serde(alias = "altitude")]
+    pub alt_baro: Option<AltBaro>,
+    /// Geometric (GNSS) altitude in feet.
+    pub alt_geom: Option<f64>,
+    /// Ground speed in knots.
+    #[serde(alias = "speed")]
+    pub gs: Option<f64>,
+    /// True track over ground in degrees.
+    pub track: Option<f64>,
+    /// Barometric vertical rate in feet per minute.
+    #[serde(alias = "vert_rate")]
+    pub baro_rate: Option<f64>,
+    pub squawk: Option<String>,
+    /// ADS-B emitter category, e.g. `"A3"`.
+    pub category: Option<String>,
+    /// Seconds since any message was last received from this aircraft.
+    pub seen: Option<f64>,
+}
+
+/// `alt_baro` is either a number of feet or the literal `"ground"`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum AltBaro {
+    Feet(f64),
+    Ground(String),
+}
+
+impl AircraftEntry {
+    /// Converts this entry into a [`Flight`] in metric units.
+    ///
+    /// `now` is the document timestamp used to turn `seen` into an absolute
+    /// last-contact time. Returns `None` when the entry has no position.
+    pub fn to_flight(&self, now: Option<f64>) -> Option<Flight> {
+        let (latitude, longitude) = (self.lat?, self.lon?);
+
+        let altitude_ft = match self.alt_baro {
+            Some(AltBaro::Feet(ft)) => ft,
+            _ => 0.0,
+        };
+
+        let hex = self.hex.trim();
+        Some(Flight {
+            icao24: hex.to_lowercase(),
+            non_icao: hex.starts_with('~'),
+            callsign: self
+                .flight
+                .as_deref()
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .unwrap_or("N/A")
+                .to_string(),
+            latitude,
+            longitude,
+            altitude: (altitude_ft * FEET_TO_METERS) as f32,
+            velocity: (self.gs.unwrap_or(0.0) * KNOTS_TO_MPS) as f32,
+            true_track: self.track.unwrap_or(0.0) as f32,
+            vertical_rate: self.baro_rate.unwrap_or(0.0) * FPM_TO_MPS,
+            squawk: self.squawk.clone(),
+            on_ground: matches!(self.alt_baro, Some(AltBaro::Ground(_))),
+            geo_altitude: self.alt_geom.map(|ft| (ft * FEET_TO_METERS) as f32),
+            category: self.category.as_deref().and_then(category_code),
+            last_contact: now.map(|n| (n - self.seen.unwrap_or(0.0)) as i64),
+            ..Default::default()
+        })
+    }
+}
+
+/// Maps an ADS-B emitter category (`"A0"`–`"C7"`) onto OpenSky's numeric
+/// category codes (see [`crate::models::category_label`]).
+fn category_code(category: &str) -> Option<u8> {
+    let mut chars = category.chars();
+    let set = chars.next()?;
+    let n = chars.as_str().parse::<u8>().ok().filter(|n| *n <= 7)?;
+    let code = match set.to_ascii_uppercase() {
+        'A' => 1 + n,
+        // B0 and C0 mean "no information"; there is no code for them.
+        'B' if n >= 1 => 8 + n,
+        'C' if (1..=5).contains(&n) => 15 + n,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Polls an `aircraft.json` over HTTP or from the local filesystem.
+pub struct Dump1090Source {
+    client: Client,
+    url: String,
+    health: Mutex<SourceHealth>,
+}
+
+impl Dump1090Source {
+    /// Creates a source for `url`, which is either an `http(s)://` URL or a
+    /// path to an `aircraft.json` file.
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(std::time::Duration::from_secs(5))
+                .build()
+                .unwrap(),
+            url: url.to_string(),
+            health: Mutex::new(SourceHealth::Pending),
+        }
+    }
+
+    async fn read_document(&self) -> Result<AircraftJson> {
+        if self.url.starts_with("http://") || self.url.starts_with("https://") {
+            Ok(self
+                .client
+                .get(&self.url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<AircraftJson>()
+                .await?)
+        } else {
+            let content = tokio::fs::read_to_string(&self.url).await?;
+            Ok(serde_json::from_str(&content)?)
+        }
+    }
+
+    async fn fetch_aircraft(&self, area: &SearchArea) -> Result<Vec<Flight>> {
+        let doc = self.read_document().await?;
+        let flights = doc
+            .aircraft
+            .iter()
+            .filter_map(|entry| entry.to_flight(doc.now))
+            .filter(|f| area.contains(f))
+            .collect();
+        Ok(flights)
+    }
+}
+
+#[async_trait]
+impl FlightSource for Dump1090Source {
+    fn name(&self) -> &str {
+        "dump1090"
+    }
+
+    async fn fetch(&self, area: &SearchArea) -> Result<Vec<Flight>> {
+        let result = self.fetch_aircraft(area).await;
+        super::record_health(&self.health, &result);
+        result
+    }
+
+    fn health(&self) -> SourceHealth {
+        self.health.lock().map(|h| h.clone()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"{
+        "now": 1700000000.5,
+        "messages": 123456,
+        "aircraft": [
+            {"hex":"a1b2c3","flight":"UAL123  ","lat":37.70,"lon":-122.40,
+             "alt_baro":35000,"gs":450.0,"track":270.5,"baro_rate":-1024,
+             "squawk":"1200","category":"A5","seen":2.5},
+            {"hex":"~2d0001","flight":"","lat":37.80,"lon":-122.30,
+             "alt_baro":"ground","gs":12.0,"seen":0.1},
+            {"hex":"abcdef","alt_baro":12000,"seen":30.0}
+        ]
+    }"#;
+
+    #[test]
+    fn test_maps_readsb_entry_to_metric_flight() {
+        let doc: AircraftJson = serde_json::from_str(SAMPLE).unwrap();
+        let flight = doc.aircraft[0].to_flight(doc.now).unwrap();
+
+        assert_eq!(flight.icao24, "a1b2c3");
+        assert_eq!(flight.callsign, "UAL123");
+        assert!((flight.altitude - 10668.0).abs() < 1.0);
+        assert!((flight.velocity - 231.5).abs() < 0.1);
+        assert!((flight.vertical_rate + 5.2).abs() < 0.01);
+        assert_eq!(flight.squawk.as_deref(), Some("1200"));
+        assert_eq!(flight.category_label(), Some("Heavy (> 300000 lbs)"));
+        assert_eq!(flight.last_contact, Some(1_699_999_998));
+    }
+
+    #[test]
+    fn test_ground_and_positionless_entries() {
+        let doc: AircraftJson = serde_json::from_str(SAMPLE).unwrap();
+
+        let on_ground = doc.aircraft[1].to_flight(doc.now).unwrap();
+        assert_eq!(on_ground.altitude, 0.0);
+        assert!(on_ground.on_ground);
+        assert_eq!(on_ground.callsign, "N/A");
+        // TIS-B targets keep the `~` so they never collide with a real
+        // address, and are flagged so lookups skip them.
+        assert_eq!(on_ground.icao24, "~2d0001");
+        assert!(on_ground.non_icao);
+        assert!(!doc.aircraft[0].to_flight(doc.now).unwrap().non_icao);
+
+        assert!(doc.aircraft[2].to_flight(doc.now).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_from_file_filters_by_radius() {
+        let path = std::env::temp_dir().join("redwood-test-aircraft.json");
+        std::fs::write(&path, SAMPLE).unwrap();
+
+        let source = Dump1090Source::new(path.to_str().unwrap());
+        let area = SearchArea {
+            lat: 37.70,
+            lon: -122.40,
+            radius_km: 5.0,
+        };
+        let flights = source.fetch(&area).await.unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(flights.len(), 1);
+        assert_eq!(flights[0].icao24, "a1b2c3");
+        assert_eq!(source.health(), SourceHealth::Online);
+    }
+}
@@ -0,0 +1,293 @@
+//! Flight data sources for the Redwood TUI
+//!
+//! This module defines the [`FlightSource`] trait that every feed implements
+//! (OpenSky, local dump1090/readsb receivers, SBS-1, Beast and AVR streams)
+//! and the [`FlightProvider`], which wraps the configured source and runs
+//! the fetch → DB enrichment → [`Event::FlightUpdate`] pipeline used by the
+//! background poller.
+
+pub mod area;
+pub mod avr;
+pub mod beast;
+pub mod dump1090;
+pub mod modes;
+pub mod opensky;
+pub mod sbs;
+pub mod schedule;
+pub mod state;
+pub mod stream;
+
+pub use area::{BoundingBox, SearchArea};
+pub use dump1090::Dump1090Source;
+pub use opensky::{OpenSkyCredentials, OpenSkySource};
+pub use stream::{StreamInput, StreamSource};
+
+use crate::config::SourceConfig;
+use crate::db::SharedRegistry;
+use crate::events::Event;
+use crate::models::Flight;
+use async_trait::async_trait;
+use color_eyre::Result;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::error;
+
+/// Receiver feeds report in aviation units; [`Flight`] is metric.
+pub(crate) const FEET_TO_METERS: f64 = 0.3048;
+pub(crate) const KNOTS_TO_MPS: f64 = 0.514_444;
+pub(crate) const FPM_TO_MPS: f64 = 0.005_08;
+
+/// Last known health of a flight source.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SourceHealth {
+    /// No fetch has completed yet.
+    #[default]
+    Pending,
+    /// The most recent fetch succeeded.
+    Online,
+    /// The most recent fetch failed; payload is the error message.
+    Error(String),
+}
+
+impl SourceHealth {
+    /// Short label for the telemetry panel.
+    pub fn label(&self) -> &'static str {
+        match self {
+            SourceHealth::Pending => "PENDING",
+            SourceHealth::Online => "ONLINE",
+            SourceHealth::Error(_) => "ERROR",
+        }
+    }
+}
+
+/// API credit state reported by a rate-limited source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Credits left in the current window, if the source reported it.
+    pub remaining: Option<u64>,
+    /// How long the source asked us to wait before the next request, if it
+    /// refused one.
+    pub retry_after: Option<Duration>,
+}
+
+/// Error returned by a source whose request was refused for lack of API
+/// credits (HTTP 429). Recover it with `report.downcast_ref::<RateLimited>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimited {
+    /// Wait requested by the server before retrying.
+    pub retry_after: Option<Duration>,
+}
+
+impl std::fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.retry_after {
+            Some(wait) => write!(f, "rate limited, retry in {}s", wait.as_secs()),
+            None => write!(f, "rate limited"),
+        }
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// A feed of aircraft state for a geographic area.
+///
+/// Implementations must be cheap to call repeatedly; the poller calls
+/// [`fetch`](FlightSource::fetch) once per poll interval and forwards the
+/// result through [`FlightProvider::poll`].
+#[async_trait]
+pub trait FlightSource: Send + Sync {
+    /// Short identifier shown in the UI and logs (e.g. `"opensky"`).
+    fn name(&self) -> &str;
+
+    /// Fetches the aircraft currently inside `area`. Implementations return
+    /// only aircraft within the circle (see [`SearchArea::contains`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the feed cannot be reached or its response cannot
+    /// be parsed.
+    async fn fetch(&self, area: &SearchArea) -> Result<Vec<Flight>>;
+
+    /// Health as of the most recent [`fetch`](FlightSource::fetch).
+    fn health(&self) -> SourceHealth;
+
+    /// Preferred delay between fetches. `None` (the default) means the
+    /// configured `poll_interval_seconds`; streaming sources override this to
+    /// publish their state table more often.
+    fn poll_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Credit state as of the most recent fetch. `None` (the default) for
+    /// sources without a rate limit.
+    fn rate_limit(&self) -> Option<RateLimit> {
+        None
+    }
+}
+
+/// Updates a source's health cell from the outcome of a fetch.
+pub(crate) fn record_health<T>(cell: &Mutex<SourceHealth>, result: &Result<T>) {
+    if let Ok(mut health) = cell.lock() {
+        *health = match result {
+            Ok(_) => SourceHealth::Online,
+            Err(e) => SourceHealth::Error(e.to_string()),
+        };
+    }
+}
+
+/// Runs the configured [`FlightSource`] and turns its output into events.
+pub struct FlightProvider {
+    source: Box<dyn FlightSource>,
+    registry: SharedRegistry,
+}
+
+impl Default for FlightProvider {
+    fn default() -> Self {
+        Self::new(Box::new(OpenSkySource::new()))
+    }
+}
+
+impl FlightProvider {
+    /// Wraps an already constructed source.
+    pub fn new(source: Box<dyn FlightSource>) -> Self {
+        Self {
+            source,
+            registry: SharedRegistry::default(),
+        }
+    }
+
+    /// Enriches through `registry` (shared with other providers) instead of
+    /// a private one.
+    pub fn with_registry(mut self, registry: SharedRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Builds the source selected in `config.toml`.
+    pub fn from_config(config: &SourceConfig) -> Self {
+        match config {
+            SourceConfig::OpenSky {
+                client_id: Some(client_id),
+                client_secret: Some(client_secret),
+            } => Self::new(Box::new(
+                OpenSkySource::new()
+                    .with_credentials(OpenSkyCredentials::new(client_id, client_secret)),
+            )),
+            SourceConfig::OpenSky { .. } => Self::default(),
+            SourceConfig::Dump1090 { url } => Self::new(Box::new(Dump1090Source::new(url))),
+            SourceConfig::Sbs {
+                address,
+                update_interval_ms,
+            } => Self::new(Box::new(StreamSource::new(
+                "sbs",
+                address,
+                Duration::from_millis(*update_interval_ms),
+                sbs::SbsDecoder::default(),
+            ))),
+            SourceConfig::Beast {
+                address,
+                update_interval_ms,
+            } => Self::new(Box::new(StreamSource::new(
+                "beast",
+                address,
+                Duration::from_millis(*update_interval_ms),
+                beast::BeastDecoder::default(),
+            ))),
+            SourceConfig::Avr {
+                address,
+                path,
+                update_interval_ms,
+            } => {
+                let input = match path.as_deref() {
+                    Some("-") => StreamInput::Stdin,
+                    Some(path) => StreamInput::File(path.into()),
+                    None => StreamInput::Tcp(
+                        address
+                            .clone()
+                            .unwrap_or_else(|| "localhost:30002".to_string()),
+                    ),
+                };
+                Self::new(Box::new(StreamSource::with_input(
+                    "avr",
+                    input,
+                    Duration::from_millis(*update_interval_ms),
+                    avr::AvrDecoder::default(),
+                )))
+            }
+        }
+    }
+
+    /// Name of the wrapped source.
+    pub fn name(&self) -> &str {
+        self.source.name()
+    }
+
+    /// Health of the wrapped source.
+    pub fn health(&self) -> SourceHealth {
+        self.source.health()
+    }
+
+    /// Credit state of the wrapped source, if it is rate limited.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.source.rate_limit()
+    }
+
+    /// Delay between polls: the source's own cadence if it has one,
+    /// otherwise `default`.
+    pub fn poll_interval(&self, default: Duration) -> Duration {
+        self.source.poll_interval().unwrap_or(default)
+    }
+
+    /// Fetches flights within `radius_km` (great-circle distance) of a point,
+    /// without DB enrichment.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any error from the underlying source.
+    pub async fn fetch_overhead(&self, lat: f64, lon: f64, radius_km: f64) -> Result<Vec<Flight>> {
+        self.source
+            .fetch(&SearchArea {
+                lat,
+                lon,
+                radius_km,
+            })
+            .await
+    }
+
+    /// Fetches `area`, enriches the result from the aircraft DB and packages it
+    /// as an [`Event::FlightUpdate`].
+    ///
+    /// The DB lookup runs on a blocking thread. A failed fetch is logged and
+    /// reported as an update with no flights and `is_success: false`.
+    pub async fn poll(&self, area: &SearchArea) -> Event {
+        match self.source.fetch(area).await {
+            Ok(flights) => {
+                // offload DB lookup to blocking thread
+                let registry = self.registry.clone();
+                let enriched = tokio::task::spawn_blocking(move || registry.decorate(flights))
+                    .await
+                    .unwrap_or_default();
+
+                Event::FlightUpdate {
+                    flights: enriched,
+                    timestamp: Instant::now(),
+                    is_success: true,
+                    source: self.name().to_string(),
+                    health: self.health(),
+                    rate_limit: self.rate_limit(),
+                }
+            }
+            Err(e) => {
+                error!(source = %self.name(), "API Fetch failed: {}", e);
+                Event::FlightUpdate {
+                    flights: Vec::new(),
+                    timestamp: Instant::now(),
+                    is_success: false,
+                    source: self.name().to_string(),
+                    health: self.health(),
+                    rate_limit: self.rate_limit(),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,708 @@
+//! Mode-S and ADS-B message decoding.
+//!
+//! Decodes raw 56/112-bit Mode-S frames as delivered by Beast or AVR feeds:
+//! - **DF17/DF18** extended squitter: identification (TC 1–4), airborne
+//!   position (TC 9–18, 20–22) and airborne velocity (TC 19).
+//! - **DF4/DF20** altitude replies and **DF5/DF21** identity (squawk) replies,
+//!   whose ICAO24 is recovered from the address/parity field.
+//!
+//! Positions use Compact Position Reporting; [`cpr_global`] resolves an
+//! even/odd pair and [`cpr_local`] resolves a single frame against a nearby
+//! reference. [`ModeSMessage::apply`] merges decoded messages into an
+//! [`AircraftTable`].
+
+use super::state::AircraftTable;
+use super::{FEET_TO_METERS, FPM_TO_MPS, KNOTS_TO_MPS};
+
+/// Mode-S parity generator polynomial (24 bits plus the implicit top bit).
+const CRC_GENERATOR: u32 = 0x1FF_F409;
+
+/// Even and odd CPR frames further apart than this are not paired.
+const CPR_PAIR_MAX_AGE_SECS: i64 = 10;
+
+/// An aircraft's own last position is used for local CPR decoding only while
+/// it is at most this old.
+const CPR_LOCAL_MAX_AGE_SECS: i64 = 60;
+
+/// 6-bit character set used in ADS-B identification messages.
+const AIS_CHARSET: &[u8; 64] = b"#ABCDEFGHIJKLMNOPQRSTUVWXYZ##### ###############0123456789######";
+
+/// A decoded Mode-S message carrying data Redwood can use.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModeSMessage {
+    /// TC 1–4: callsign and emitter category.
+    Identification {
+        icao24: u32,
+        callsign: String,
+        /// Emitter category (ME bits 6–8) within the type code's set.
+        category: u8,
+    },
+    /// TC 9–18 / 20–22: altitude plus one CPR-encoded position.
+    AirbornePosition {
+        icao24: u32,
+        /// Barometric altitude in feet (`None` for GNSS-height type codes or
+        /// when unavailable).
+        altitude_ft: Option<i32>,
+        cpr: CprFormat,
+        lat_cpr: u32,
+        lon_cpr: u32,
+    },
+    /// TC 19: ground speed and track (subtypes 1/2) or airspeed and heading
+    /// (subtypes 3/4), plus vertical rate.
+    Velocity {
+        icao24: u32,
+        /// Speed in knots.
+        speed_kt: f64,
+        /// Track (or heading, for airspeed subtypes) in degrees.
+        track: f64,
+        /// Vertical rate in feet per minute.
+        vertical_rate_fpm: Option<i32>,
+    },
+    /// DF4/DF20: altitude reply.
+    Altitude { icao24: u32, altitude_ft: i32 },
+    /// DF5/DF21: identity reply.
+    Identity { icao24: u32, squawk: String },
+}
+
+impl ModeSMessage {
+    /// ICAO24 address the message refers to.
+    pub fn icao24(&self) -> u32 {
+        match *self {
+            ModeSMessage::Identification { icao24, .. }
+            | ModeSMessage::AirbornePosition { icao24, .. }
+            | ModeSMessage::Velocity { icao24, .. }
+            | ModeSMessage::Altitude { icao24, .. }
+            | ModeSMessage::Identity { icao24, .. } => icao24,
+        }
+    }
+
+    /// Merges this message into `table`, stamping the aircraft with `now`.
+    ///
+    /// DF4/5/20/21 replies cannot be parity-checked (the parity field holds
+    /// the address), so they are only applied to aircraft already known from
+    /// a CRC-verified extended squitter.
+    pub fn apply(&self, table: &mut AircraftTable, now: i64) {
+        let icao = format!("{:06x}", self.icao24());
+        if matches!(
+            self,
+            ModeSMessage::Altitude { .. } | ModeSMessage::Identity { .. }
+        ) && !table.contains(&icao)
+        {
+            return;
+        }
+
+        let reference = table.reference;
+        let entry = table.update(&icao, now);
+        match self {
+            ModeSMessage::Identification { callsign, .. } => {
+                if !callsign.is_empty() {
+                    entry.flight.callsign = callsign.clone();
+                }
+            }
+            ModeSMessage::AirbornePosition {
+                altitude_ft,
+                cpr,
+                lat_cpr,
+                lon_cpr,
+                ..
+            } => {
+                if let Some(alt) = altitude_ft {
+                    entry.flight.altitude = (*alt as f64 * FEET_TO_METERS) as f32;
+                }
+
+                let frame = CprFrame {
+                    lat: *lat_cpr,
+                    lon: *lon_cpr,
+                    time: now,
+                };
+                let other = match cpr {
+                    CprFormat::Even => {
+                        entry.cpr_even = Some(frame);
+                        entry.cpr_odd
+                    }
+                    CprFormat::Odd => {
+                        entry.cpr_odd = Some(frame);
+                        entry.cpr_even
+                    }
+                };
+
+                let paired = other
+                    .filter(|o| (now - o.time).abs() <= CPR_PAIR_MAX_AGE_SECS)
+                    .and_then(|o| match cpr {
+                        CprFormat::Even => cpr_global(&frame, &o, *cpr),
+                        CprFormat::Odd => cpr_global(&o, &frame, *cpr),
+                    });
+                let own_reference = entry
+                    .position_time
+                    .filter(|t| now - t <= CPR_LOCAL_MAX_AGE_SECS)
+                    .map(|_| (entry.flight.latitude, entry.flight.longitude));
+
+                let position = paired.or_else(|| {
+                    own_reference
+                        .or(reference)
+                        .map(|(lat, lon)| cpr_local(lat, lon, &frame, *cpr))
+                });
+                if let Some((lat, lon)) = position {
+                    entry.set_position(lat, lon, now);
+                }
+            }
+            ModeSMessage::Velocity {
+                speed_kt,
+                track,
+                vertical_rate_fpm,
+                ..
+            } => {
+                entry.flight.velocity = (speed_kt * KNOTS_TO_MPS) as f32;
+                entry.flight.true_track = *track as f32;
+                if let Some(vr) = vertical_rate_fpm {
+                    entry.flight.vertical_rate = *vr as f64 * FPM_TO_MPS;
+                }
+            }
+            ModeSMessage::Altitude { altitude_ft, .. } => {
+                entry.flight.altitude = (*altitude_ft as f64 * FEET_TO_METERS) as f32;
+            }
+            ModeSMessage::Identity { squawk, .. } => {
+                entry.flight.squawk = Some(squawk.clone());
+            }
+        }
+    }
+}
+
+/// CPR format flag of a position message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CprFormat {
+    Even,
+    Odd,
+}
+
+/// One CPR-encoded position report and when it was received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CprFrame {
+    /// 17-bit encoded latitude.
+    pub lat: u32,
+    /// 17-bit encoded longitude.
+    pub lon: u32,
+    /// Unix time (seconds) the frame was received.
+    pub time: i64,
+}
+
+/// Computes the 24-bit Mode-S parity over `data`.
+///
+/// For a valid DF17/DF18 frame the parity over all 14 bytes is zero. For
+/// DF4/5/20/21 the parity over all but the last three bytes, XORed with
+/// those bytes, yields the ICAO24 address.
+pub fn crc24(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0;
+    for &byte in data {
+        crc ^= (byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x100_0000 != 0 {
+                crc ^= CRC_GENERATOR;
+            }
+        }
+    }
+    crc & 0xFF_FFFF
+}
+
+/// Reads `len` bits (at most 32) starting at bit `start` (0 = MSB of `data[0]`).
+fn bits(data: &[u8], start: usize, len: usize) -> u32 {
+    (start..start + len).fold(0, |acc, i| {
+        let bit = (data[i / 8] >> (7 - i % 8)) & 1;
+        (acc << 1) | bit as u32
+    })
+}
+
+/// Decodes a raw Mode-S frame (7 or 14 bytes).
+///
+/// Returns `None` for frames that fail the CRC check, are the wrong length
+/// for their downlink format, or carry nothing Redwood uses (e.g. surface
+/// positions, DF11 all-call replies, non-ICAO DF18 addresses).
+pub fn decode(msg: &[u8]) -> Option<ModeSMessage> {
+    let df = msg.first()? >> 3;
+    let expected_len = if df >= 16 { 14 } else { 7 };
+    if msg.len() != expected_len {
+        return None;
+    }
+
+    match df {
+        17 | 18 => {
+            // DF18 with CF != 0 carries non-ICAO or TIS-B addresses.
+            if df == 18 && msg[0] & 0x07 != 0 {
+                return None;
+            }
+            if crc24(msg) != 0 {
+                return None;
+            }
+            decode_extended_squitter(msg)
+        }
+        4 | 20 => {
+            let icao24 = crc24(&msg[..msg.len() - 3]) ^ bits(msg, (msg.len() - 3) * 8, 24);
+            let altitude_ft = decode_ac13(bits(msg, 19, 13))?;
+            Some(ModeSMessage::Altitude {
+                icao24,
+                altitude_ft,
+            })
+        }
+        5 | 21 => {
+            let icao24 = crc24(&msg[..msg.len() - 3]) ^ bits(msg, (msg.len() - 3) * 8, 24);
+            Some(ModeSMessage::Identity {
+                icao24,
+                squawk: decode_id13(bits(msg, 19, 13)),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Decodes the ME field of a CRC-checked DF17/DF18 frame.
+fn decode_extended_squitter(msg: &[u8]) -> Option<ModeSMessage> {
+    let icao24 = bits(msg, 8, 24);
+    let me = &msg[4..11];
+    let tc = bits(me, 0, 5);
+
+    match tc {
+        1..=4 => {
+            let callsign: String = (0..8)
+                .map(|i| AIS_CHARSET[bits(me, 8 + i * 6, 6) as usize] as char)
+                .filter(|&c| c != '#')
+                .collect();
+            Some(ModeSMessage::Identification {
+                icao24,
+                callsign: callsign.trim().to_string(),
+                category: bits(me, 5, 3) as u8,
+            })
+        }
+        9..=18 | 20..=22 => {
+            let altitude_ft = if tc <= 18 {
+                decode_ac12(bits(me, 8, 12))
+            } else {
+                None
+            };
+            Some(ModeSMessage::AirbornePosition {
+                icao24,
+                altitude_ft,
+                cpr: if bits(me, 21, 1) == 1 {
+                    CprFormat::Odd
+                } else {
+                    CprFormat::Even
+                },
+                lat_cpr: bits(me, 22, 17),
+                lon_cpr: bits(me, 39, 17),
+            })
+        }
+        19 => decode_velocity(icao24, me),
+        _ => None,
+    }
+}
+
+/// Decodes an airborne velocity ME field (TC 19).
+fn decode_velocity(icao24: u32, me: &[u8]) -> Option<ModeSMessage> {
+    let subtype = bits(me, 5, 3);
+    let (speed_kt, track) = match subtype {
+        1 | 2 => {
+            let scale = if subtype == 2 { 4.0 } else { 1.0 };
+            let (v_ew, v_ns) = (bits(me, 14, 10), bits(me, 25, 10));
+            if v_ew == 0 || v_ns == 0 {
+                return None;
+            }
+            let sign = |bit: u32| if bit == 1 { -1.0 } else { 1.0 };
+            let vx = sign(bits(me, 13, 1)) * (v_ew - 1) as f64 * scale;
+            let vy = sign(bits(me, 24, 1)) * (v_ns - 1) as f64 * scale;
+            let track = vx.atan2(vy).to_degrees().rem_euclid(360.0);
+            (vx.hypot(vy), track)
+        }
+        3 | 4 => {
+            let scale = if subtype == 4 { 4.0 } else { 1.0 };
+            let airspeed = bits(me, 25, 10);
+            if bits(me, 13, 1) == 0 || airspeed == 0 {
+                return None;
+            }
+            let heading = bits(me, 14, 10) as f64 * 360.0 / 1024.0;
+            ((airspeed - 1) as f64 * scale, heading)
+        }
+        _ => return None,
+    };
+
+    let vr = bits(me, 37, 9);
+    let vertical_rate_fpm = (vr != 0).then(|| {
+        let rate = (vr as i32 - 1) * 64;
+        if bits(me, 36, 1) == 1 {
+            -rate
+        } else {
+            rate
+        }
+    });
+
+    Some(ModeSMessage::Velocity {
+        icao24,
+        speed_kt,
+        track,
+        vertical_rate_fpm,
+    })
+}
+
+/// Decodes the 12-bit altitude field of an airborne position message.
+fn decode_ac12(ac12: u32) -> Option<i32> {
+    if ac12 == 0 {
+        return None;
+    }
+    if ac12 & 0x10 != 0 {
+        let n = ((ac12 & 0x0FE0) >> 1) | (ac12 & 0x000F);
+        Some(n as i32 * 25 - 1000)
+    } else {
+        // Re-insert the M bit to reuse the 13-bit Gillham decoder.
+        let ac13 = ((ac12 & 0x0FC0) << 1) | (ac12 & 0x003F);
+        gillham_altitude(ac13)
+    }
+}
+
+/// Decodes the 13-bit altitude code of a DF4/DF20 reply.
+fn decode_ac13(ac13: u32) -> Option<i32> {
+    if ac13 == 0 || ac13 & 0x40 != 0 {
+        // Unavailable, or metric altitude (M bit) which is not in use.
+        return None;
+    }
+    if ac13 & 0x10 != 0 {
+        let n = ((ac13 & 0x1F80) >> 2) | ((ac13 & 0x0020) >> 1) | (ac13 & 0x000F);
+        Some(n as i32 * 25 - 1000)
+    } else {
+        gillham_altitude(ac13)
+    }
+}
+
+/// Reorders a 13-bit identity/altitude field into Gillham `0xABCD` order,
+/// one octal digit per nibble.
+fn id13_to_gillham(id13: u32) -> u32 {
+    const MAP: [(u32, u32); 12] = [
+        (0x1000, 0x0010), // C1
+        (0x0800, 0x1000), // A1
+        (0x0400, 0x0020), // C2
+        (0x0200, 0x2000), // A2
+        (0x0100, 0x0040), // C4
+        (0x0080, 0x4000), // A4
+        (0x0020, 0x0100), // B1
+        (0x0010, 0x0001), // D1
+        (0x0008, 0x0200), // B2
+        (0x0004, 0x0002), // D2
+        (0x0002, 0x0400), // B4
+        (0x0001, 0x0004), // D4
+    ];
+    MAP.iter()
+        .filter(|(from, _)| id13 & from != 0)
+        .fold(0, |acc, (_, to)| acc | to)
+}
+
+/// Decodes a DF5/DF21 identity field into a four-digit squawk.
+fn decode_id13(id13: u32) -> String {
+    format!("{:04x}", id13_to_gillham(id13))
+}
+
+/// Converts a Gillham-coded 13-bit altitude field into feet (100 ft steps).
+fn gillham_altitude(ac13: u32) -> Option<i32> {
+    let code = id13_to_gillham(ac13);
+    if code & 0xFFFF_8889 != 0 || code & 0x0000_00F0 == 0 {
+        return None;
+    }
+
+    let mut one_hundreds: i32 = 0;
+    if code & 0x0010 != 0 {
+        one_hundreds ^= 0x007; // C1
+    }
+    if code & 0x0020 != 0 {
+        one_hundreds ^= 0x003; // C2
+    }
+    if code & 0x0040 != 0 {
+        one_hundreds ^= 0x001; // C4
+    }
+    // Swap 5s and 7s, then only 1..=5 remain valid.
+    if one_hundreds & 5 == 5 {
+        one_hundreds ^= 2;
+    }
+    if one_hundreds > 5 {
+        return None;
+    }
+
+    let mut five_hundreds: i32 = 0;
+    for (mask, flip) in [
+        (0x0002, 0x0FF), // D2
+        (0x0004, 0x07F), // D4
+        (0x1000, 0x03F), // A1
+        (0x2000, 0x01F), // A2
+        (0x4000, 0x00F), // A4
+        (0x0100, 0x007), // B1
+        (0x0200, 0x003), // B2
+        (0x0400, 0x001), // B4
+    ] {
+        if code & mask != 0 {
+            five_hundreds ^= flip;
+        }
+    }
+    if five_hundreds & 1 != 0 {
+        one_hundreds = 6 - one_hundreds;
+    }
+
+    Some((five_hundreds * 5 + one_hundreds - 13) * 100)
+}
+
+/// Number of CPR longitude zones at latitude `lat` (the NL function).
+pub fn cpr_nl(lat: f64) -> u32 {
+    let lat = lat.abs();
+    if lat < 1e-9 {
+        return 59;
+    }
+    if lat > 87.0 {
+        return 1;
+    }
+    if (lat - 87.0).abs() < 1e-9 {
+        return 2;
+    }
+    const NZ: f64 = 15.0;
+    let a = 1.0 - (std::f64::consts::PI / (2.0 * NZ)).cos();
+    let b = lat.to_radians().cos().powi(2);
+    (2.0 * std::f64::consts::PI / (1.0 - a / b).acos()).floor() as u32
+}
+
+const CPR_SCALE: f64 = 131_072.0; // 2^17
+
+/// Globally unambiguous position from an even/odd frame pair.
+///
+/// `newest` says which of the two frames was received last; its latitude
+/// zone is used for the longitude solution. Returns `None` if the two frames
+/// straddle a longitude-zone boundary (a newer pair is needed).
+pub fn cpr_global(even: &CprFrame, odd: &CprFrame, newest: CprFormat) -> Option<(f64, f64)> {
+    let (lat0, lon0) = (even.lat as f64 / CPR_SCALE, even.lon as f64 / CPR_SCALE);
+    let (lat1, lon1) = (odd.lat as f64 / CPR_SCALE, odd.lon as f64 / CPR_SCALE);
+    let (dlat0, dlat1) = (360.0 / 60.0, 360.0 / 59.0);
+
+    let j = (59.0 * lat0 - 60.0 * lat1 + 0.5).floor();
+    let mut rlat0 = dlat0 * (j.rem_euclid(60.0) + lat0);
+    let mut rlat1 = dlat1 * (j.rem_euclid(59.0) + lat1);
+    if rlat0 >= 270.0 {
+        rlat0 -= 360.0;
+    }
+    if rlat1 >= 270.0 {
+        rlat1 -= 360.0;
+    }
+    if !(-90.0..=90.0).contains(&rlat0) || !(-90.0..=90.0).contains(&rlat1) {
+        return None;
+    }
+    if cpr_nl(rlat0) != cpr_nl(rlat1) {
+        return None;
+    }
+
+    let (lat, lon_cpr, ni) = match newest {
+        CprFormat::Even => (rlat0, lon0, cpr_nl(rlat0).max(1)),
+        CprFormat::Odd => (rlat1, lon1, (cpr_nl(rlat1) - 1).max(1)),
+    };
+    let nl = cpr_nl(lat) as f64;
+    let m = (lon0 * (nl - 1.0) - lon1 * nl + 0.5).floor();
+    let ni = ni as f64;
+    let mut lon = (360.0 / ni) * (m.rem_euclid(ni) + lon_cpr);
+    if lon >= 180.0 {
+        lon -= 360.0;
+    }
+    Some((lat, lon))
+}
+
+/// Position from a single frame, resolved against a reference position
+/// within about 180 NM of the aircraft.
+pub fn cpr_local(ref_lat: f64, ref_lon: f64, frame: &CprFrame, format: CprFormat) -> (f64, f64) {
+    let i = match format {
+        CprFormat::Even => 0.0,
+        CprFormat::Odd => 1.0,
+    };
+    let (lat_cpr, lon_cpr) = (frame.lat as f64 / CPR_SCALE, frame.lon as f64 / CPR_SCALE);
+
+    let dlat = 360.0 / (60.0 - i);
+    let j = (ref_lat / dlat).floor() + (ref_lat.rem_euclid(dlat) / dlat - lat_cpr + 0.5).floor();
+    let lat = dlat * (j + lat_cpr);
+
+    let dlon = 360.0 / (cpr_nl(lat) as f64 - i).max(1.0);
+    let m = (ref_lon / dlon).floor() + (ref_lon.rem_euclid(dlon) / dlon - lon_cpr + 0.5).floor();
+    let mut lon = dlon * (m + lon_cpr);
+    if lon >= 180.0 {
+        lon -= 360.0;
+    }
+    (lat, lon)
+}
+
+/// Parses a hex string (e.g. `"8D4840D6202CC371C32CE0576098"`) into bytes.
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).ok().filter(|p| p.len() == 2)?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Vectors from "The 1090 Megahertz Riddle" (Junzi Sun) and pyModeS.
+    const IDENT: &str = "8D4840D6202CC371C32CE0576098";
+    const POS_EVEN: &str = "8D40621D58C382D690C8AC2863A7";
+    const POS_ODD: &str = "8D40621D58C386435CC412692AD6";
+    const VEL_GROUND: &str = "8D485020994409940838175B284F";
+    const VEL_AIR: &str = "8DA05F219B06B6AF189400CBC33F";
+    const DF4_ALT: &str = "2000171806A983";
+    const DF5_ID: &str = "2A00516D492B80";
+
+    fn decode_hex(hex: &str) -> Option<ModeSMessage> {
+        decode(&from_hex(hex).unwrap())
+    }
+
+    #[test]
+    fn test_crc_rejects_corrupted_frames() {
+        let mut msg = from_hex(IDENT).unwrap();
+        assert_eq!(crc24(&msg), 0);
+        msg[6] ^= 0x01;
+        assert!(decode(&msg).is_none());
+    }
+
+    #[test]
+    fn test_identification() {
+        assert_eq!(
+            decode_hex(IDENT),
+            Some(ModeSMessage::Identification {
+                icao24: 0x4840D6,
+                callsign: "KLM1023".to_string(),
+                category: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_airborne_position_fields() {
+        match decode_hex(POS_EVEN).unwrap() {
+            ModeSMessage::AirbornePosition {
+                icao24,
+                altitude_ft,
+                cpr,
+                lat_cpr,
+                lon_cpr,
+            } => {
+                assert_eq!(icao24, 0x40621D);
+                assert_eq!(altitude_ft, Some(38000));
+                assert_eq!(cpr, CprFormat::Even);
+                assert_eq!(lat_cpr, 93000);
+                assert_eq!(lon_cpr, 51372);
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cpr_global_and_local() {
+        let frame = |hex: &str| match decode_hex(hex).unwrap() {
+            ModeSMessage::AirbornePosition {
+                lat_cpr, lon_cpr, ..
+            } => CprFrame {
+                lat: lat_cpr,
+                lon: lon_cpr,
+                time: 0,
+            },
+            other => panic!("unexpected message {:?}", other),
+        };
+        let (even, odd) = (frame(POS_EVEN), frame(POS_ODD));
+
+        let (lat, lon) = cpr_global(&even, &odd, CprFormat::Even).unwrap();
+        assert!((lat - 52.25720).abs() < 1e-4, "lat {}", lat);
+        assert!((lon - 3.91937).abs() < 1e-4, "lon {}", lon);
+
+        let (lat, lon) = cpr_local(52.258, 3.918, &even, CprFormat::Even);
+        assert!((lat - 52.25720).abs() < 1e-4, "lat {}", lat);
+        assert!((lon - 3.91937).abs() < 1e-4, "lon {}", lon);
+    }
+
+    #[test]
+    fn test_nl_table_edges() {
+        assert_eq!(cpr_nl(0.0), 59);
+        assert_eq!(cpr_nl(52.2572), 36);
+        assert_eq!(cpr_nl(-52.2572), 36);
+        assert_eq!(cpr_nl(87.0), 2);
+        assert_eq!(cpr_nl(89.0), 1);
+    }
+
+    #[test]
+    fn test_velocity_subtypes() {
+        match decode_hex(VEL_GROUND).unwrap() {
+            ModeSMessage::Velocity {
+                icao24,
+                speed_kt,
+                track,
+                vertical_rate_fpm,
+            } => {
+                assert_eq!(icao24, 0x485020);
+                assert!((speed_kt - 159.20).abs() < 0.01);
+                assert!((track - 182.88).abs() < 0.01);
+                assert_eq!(vertical_rate_fpm, Some(-832));
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+
+        match decode_hex(VEL_AIR).unwrap() {
+            ModeSMessage::Velocity {
+                speed_kt,
+                track,
+                vertical_rate_fpm,
+                ..
+            } => {
+                assert_eq!(speed_kt, 375.0);
+                assert!((track - 243.98).abs() < 0.01);
+                assert_eq!(vertical_rate_fpm, Some(-2304));
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_surveillance_replies() {
+        match decode_hex(DF4_ALT).unwrap() {
+            ModeSMessage::Altitude { altitude_ft, .. } => assert_eq!(altitude_ft, 36000),
+            other => panic!("unexpected message {:?}", other),
+        }
+        match decode_hex(DF5_ID).unwrap() {
+            ModeSMessage::Identity { squawk, .. } => assert_eq!(squawk, "0356"),
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_gillham_altitude() {
+        // AC13 bit positions: C1 = 0x1000, C4 = 0x0100, B4 = 0x0002.
+        // The 100 ft digit cycles C1C2C4 = 001, 011, 010, 110, 100 from
+        // -1,200 ft; B4 starts the next 500 ft band in reflected order.
+        assert_eq!(gillham_altitude(0x0100), Some(-1200));
+        assert_eq!(gillham_altitude(0x0500), Some(-1100));
+        assert_eq!(gillham_altitude(0x1000), Some(-800));
+        assert_eq!(gillham_altitude(0x1002), Some(-700));
+        // No C bits set is never a valid altitude.
+        assert_eq!(gillham_altitude(0x0002), None);
+    }
+
+    #[test]
+    fn test_apply_merges_into_table() {
+        let mut table = AircraftTable::new();
+        for (hex, t) in [(IDENT, 0), (POS_ODD, 0), (POS_EVEN, 2), (VEL_GROUND, 3)] {
+            decode_hex(hex)
+                .unwrap()
+                .apply(&mut table, 1_457_996_400 + t);
+        }
+
+        let klm = table.get("4840d6").unwrap();
+        assert_eq!(klm.flight.callsign, "KLM1023");
+
+        let positioned = table.get("40621d").unwrap();
+        assert!((positioned.flight.latitude - 52.2572).abs() < 1e-4);
+        assert!((positioned.flight.altitude - 11582.4).abs() < 0.1);
+        assert!(positioned.position_time.is_some());
+
+        // Identity replies are ignored for aircraft not yet in the table.
+        decode_hex(DF5_ID).unwrap().apply(&mut table, 1_457_996_405);
+        assert_eq!(table.len(), 3);
+    }
+}
@@ -0,0 +1,498 @@
+//! OpenSky Network flight source.
+//!
+//! Queries the OpenSky `states/all` endpoint with the bounding box(es) of the
+//! search area (requesting the extended vector, which adds the aircraft
+//! category), converts each state vector into a [`Flight`] and keeps only
+//! the aircraft inside the search circle.
+//!
+//! Requests are anonymous unless [`OpenSkyCredentials`] are supplied, in which
+//! case an access token is obtained with the OAuth2 client-credentials flow,
+//! refreshed shortly before it expires and sent as a bearer token. Every
+//! response's `X-Rate-Limit-Remaining` header is recorded, and a refused
+//! request (HTTP 429) becomes a [`RateLimited`] error carrying
+//! `X-Rate-Limit-Retry-After-Seconds`.
+
+use super::{BoundingBox, FlightSource, RateLimit, RateLimited, SearchArea, SourceHealth};
+use crate::models::{Flight, OpenSkyResponse};
+use async_trait::async_trait;
+use color_eyre::{eyre::WrapErr, Result};
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Response, StatusCode};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Base URL of the public OpenSky REST API.
+pub const OPENSKY_BASE_URL: &str = "https://opensky-network.org/api";
+
+/// OpenSky's OAuth2 token endpoint for API clients.
+pub const OPENSKY_TOKEN_URL: &str =
+    "https://auth.opensky-network.org/auth/realms/opensky-network/protocol/openid-connect/token";
+
+/// Tokens are refreshed this long before they expire, so a request never
+/// goes out with one that lapses in flight.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(30);
+
+/// Credits left in the current window.
+const REMAINING_HEADER: &str = "x-rate-limit-remaining";
+/// Seconds to wait after a 429 before credits are available again.
+const RETRY_AFTER_HEADER: &str = "x-rate-limit-retry-after-seconds";
+
+/// API client registered on an OpenSky account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenSkyCredentials {
+    pub client_id: String,
+    pub client_secret: String,
+    /// Token endpoint; [`OPENSKY_TOKEN_URL`] unless overridden (e.g. in tests).
+    pub token_url: String,
+}
+
+impl OpenSkyCredentials {
+    /// Credentials for the public OpenSky token endpoint.
+    pub fn new(client_id: &str, client_secret: &str) -> Self {
+        Self {
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            token_url: OPENSKY_TOKEN_URL.to_string(),
+        }
+    }
+}
+
+/// Body of a successful token response.
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    /// Lifetime in seconds.
+    #[serde(default)]
+    expires_in: u64,
+}
+
+struct AccessToken {
+    value: String,
+    expires_at: Instant,
+}
+
+/// This struct manages HTTP client config and handles
+/// fetching real-time flight data within a specified geographic radius.
+pub struct OpenSkySource {
+    client: Client,
+    base_url: String,
+    credentials: Option<OpenSkyCredentials>,
+    token: tokio::sync::Mutex<Option<AccessToken>>,
+    rate_limit: Mutex<RateLimit>,
+    health: Mutex<SourceHealth>,
+}
+
+impl Default for OpenSkySource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OpenSkySource {
+    /// Creates an anonymous source pointed at the public OpenSky API.
+    pub fn new() -> Self {
+        Self::with_base_url(OPENSKY_BASE_URL)
+    }
+
+    /// Creates a source pointed at an alternative API root (e.g. a local
+    /// stand-in server in tests). `base_url` must not end with a slash.
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap(),
+            base_url: base_url.to_string(),
+            credentials: None,
+            token: tokio::sync::Mutex::new(None),
+            rate_limit: Mutex::new(RateLimit::default()),
+            health: Mutex::new(SourceHealth::Pending),
+        }
+    }
+
+    /// Authenticates requests with an OpenSky API client.
+    pub fn with_credentials(mut self, credentials: OpenSkyCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Returns a valid access token, requesting a new one if there is none or
+    /// it is about to expire. `None` for anonymous sources.
+    async fn bearer_token(&self) -> Result<Option<String>> {
+        let Some(credentials) = &self.credentials else {
+            return Ok(None);
+        };
+
+        let mut token = self.token.lock().await;
+        if let Some(current) = token.as_ref() {
+            if current.expires_at > Instant::now() + TOKEN_REFRESH_MARGIN {
+                return Ok(Some(current.value.clone()));
+            }
+        }
+
+        let response = self
+            .client
+            .post(&credentials.token_url)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", credentials.client_id.as_str()),
+                ("client_secret", credentials.client_secret.as_str()),
+            ])
+            .send()
+            .await
+            .and_then(Response::error_for_status)
+            .wrap_err("OpenSky token request failed")?
+            .json::<TokenResponse>()
+            .await
+            .wrap_err("OpenSky token response was malformed")?;
+
+        debug!(
+            expires_in = response.expires_in,
+            "obtained OpenSky access token"
+        );
+        *token = Some(AccessToken {
+            value: response.access_token.clone(),
+            expires_at: Instant::now() + Duration::from_secs(response.expires_in),
+        });
+        Ok(Some(response.access_token))
+    }
+
+    async fn get(&self, url: &str) -> Result<Response> {
+        let mut request = self.client.get(url);
+        if let Some(token) = self.bearer_token().await? {
+            request = request.bearer_auth(token);
+        }
+        Ok(request.send().await?)
+    }
+
+    /// Stores the credit headers of a response.
+    fn record_rate_limit(&self, headers: &HeaderMap) {
+        let parsed = parse_rate_limit(headers);
+        if let Ok(mut limit) = self.rate_limit.lock() {
+            if parsed.remaining.is_some() {
+                limit.remaining = parsed.remaining;
+            }
+            limit.retry_after = parsed.retry_after;
+        }
+    }
+
+    /// Queries every bounding box of `area` (two across the antimeridian)
+    /// and keeps the aircraft inside the circle itself.
+    async fn fetch_states(&self, area: &SearchArea) -> Result<Vec<Flight>> {
+        let mut seen = HashSet::new();
+        let mut flights = Vec::new();
+        for bbox in area.bounding_boxes() {
+            for flight in self.fetch_box(&bbox).await? {
+                if area.contains(&flight) && seen.insert(flight.icao24.clone()) {
+                    flights.push(flight);
+                }
+            }
+        }
+        Ok(flights)
+    }
+
+    async fn fetch_box(&self, bbox: &BoundingBox) -> Result<Vec<Flight>> {
+        let url = format!(
+            "{}/states/all?lamin={}&lomin={}&lamax={}&lomax={}&extended=1",
+            self.base_url, bbox.lamin, bbox.lomin, bbox.lamax, bbox.lomax
+        );
+
+        let mut response = self.get(&url).await?;
+        if response.status() == StatusCode::UNAUTHORIZED && self.credentials.is_some() {
+            // Token revoked before its advertised expiry; retry once with a
+            // fresh one.
+            warn!("OpenSky rejected the access token, requesting a new one");
+            *self.token.lock().await = None;
+            response = self.get(&url).await?;
+        }
+
+        self.record_rate_limit(response.headers());
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            return Err(RateLimited {
+                retry_after: parse_rate_limit(response.headers()).retry_after,
+            }
+            .into());
+        }
+
+        let res = response
+            .error_for_status()?
+            .json::<OpenSkyResponse>()
+            .await?;
+
+        Ok(res.into_flights())
+    }
+}
+
+/// Reads OpenSky's credit headers; missing or malformed values are `None`.
+fn parse_rate_limit(headers: &HeaderMap) -> RateLimit {
+    let number = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+    RateLimit {
+        remaining: number(REMAINING_HEADER),
+        retry_after: number(RETRY_AFTER_HEADER).map(Duration::from_secs),
+    }
+}
+
+#[async_trait]
+impl FlightSource for OpenSkySource {
+    fn name(&self) -> &str {
+        "opensky"
+    }
+
+    async fn fetch(&self, area: &SearchArea) -> Result<Vec<Flight>> {
+        // A Retry-After only describes the response it came with.
+        if let Ok(mut limit) = self.rate_limit.lock() {
+            limit.retry_after = None;
+        }
+        let result = self.fetch_states(area).await;
+        super::record_health(&self.health, &result);
+        result
+    }
+
+    fn health(&self) -> SourceHealth {
+        self.health.lock().map(|h| h.clone()).unwrap_or_default()
+    }
+
+    fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit.lock().ok().map(|limit| *limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const STATES: &str = r#"{"time":1700000000,"states":[
+        ["4840d6","KLM1023 ","Kingdom of the Netherlands",1700000000,1700000000,
+         4.76,52.31,3000.0,false,120.0,90.0,5.0,null,3100.0,"1000",false,0]
+    ]}"#;
+
+    /// Behaviour of the stand-in server.
+    #[derive(Clone, Copy)]
+    struct Script {
+        /// `expires_in` of issued tokens.
+        token_lifetime: u64,
+        /// Answer every states request with 429.
+        exhausted: bool,
+    }
+
+    /// Minimal stand-in for OpenSky's token and REST endpoints. Issues
+    /// tokens `tok-1`, `tok-2`, … and only serves states to the latest one.
+    /// Returns the base URL and the log of `METHOD /path` request lines.
+    async fn stand_in(script: Script) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let requests = Arc::clone(&log);
+
+        tokio::spawn(async move {
+            let mut issued = 0;
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let request = read_request(&mut socket).await;
+                let request_line = request.lines().next().unwrap_or_default().to_string();
+                let path = request_line
+                    .split(' ')
+                    .take(2)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                requests.lock().unwrap().push(path.clone());
+
+                let response = if path.starts_with("POST /token") {
+                    if !request.contains("grant_type=client_credentials")
+                        || !request.contains("client_secret=s3cret")
+                    {
+                        http(401, &[], "{}")
+                    } else {
+                        issued += 1;
+                        let body = format!(
+                            r#"{{"access_token":"tok-{}","expires_in":{},"token_type":"Bearer"}}"#,
+                            issued, script.token_lifetime
+                        );
+                        http(200, &[], &body)
+                    }
+                } else if script.exhausted {
+                    http(
+                        429,
+                        &[
+                            ("X-Rate-Limit-Remaining", "0"),
+                            ("X-Rate-Limit-Retry-After-Seconds", "120"),
+                        ],
+                        "",
+                    )
+                } else if request.contains(&format!("authorization: Bearer tok-{}", issued)) {
+                    http(200, &[("X-Rate-Limit-Remaining", "3996")], STATES)
+                } else {
+                    http(401, &[], "")
+                };
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (base, log)
+    }
+
+    /// Reads headers and any `Content-Length` body; header names lowercased.
+    async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let n = socket.read(&mut chunk).await.unwrap_or(0);
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+            let text = String::from_utf8_lossy(&buf).to_string();
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .find_map(|l| {
+                        l.to_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                    })
+                    .unwrap_or(0);
+                if buf.len() >= end + 4 + length {
+                    break;
+                }
+            }
+        }
+        let text = String::from_utf8_lossy(&buf);
+        text.lines()
+            .map(|line| match line.split_once(':') {
+                Some((name, value)) => format!("{}:{}", name.to_lowercase(), value),
+                None => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn http(status: u16, headers: &[(&str, &str)], body: &str) -> String {
+        let mut out = format!(
+            "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            status,
+            body.len()
+        );
+        for (name, value) in headers {
+            out.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        out.push_str("\r\n");
+        out.push_str(body);
+        out
+    }
+
+    fn source(base: &str) -> OpenSkySource {
+        OpenSkySource::with_base_url(base).with_credentials(OpenSkyCredentials {
+            client_id: "redwood".to_string(),
+            client_secret: "s3cret".to_string(),
+            token_url: format!("{}/token", base),
+        })
+    }
+
+    const AREA: SearchArea = SearchArea {
+        lat: 52.3,
+        lon: 4.76,
+        radius_km: 50.0,
+    };
+
+    fn token_requests(log: &Mutex<Vec<String>>) -> usize {
+        log.lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.starts_with("POST /token"))
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_token_reused_and_credits_recorded() {
+        let (base, log) = stand_in(Script {
+            token_lifetime: 1800,
+            exhausted: false,
+        })
+        .await;
+        let source = source(&base);
+
+        for _ in 0..2 {
+            let flights = source.fetch(&AREA).await.unwrap();
+            assert_eq!(flights.len(), 1);
+            assert_eq!(flights[0].callsign, "KLM1023");
+        }
+
+        assert_eq!(token_requests(&log), 1);
+        assert_eq!(source.rate_limit().unwrap().remaining, Some(3996));
+        assert_eq!(source.health(), SourceHealth::Online);
+    }
+
+    #[tokio::test]
+    async fn test_token_refreshed_before_expiry() {
+        // Lifetimes inside the refresh margin force a new token per request.
+        let (base, log) = stand_in(Script {
+            token_lifetime: 10,
+            exhausted: false,
+        })
+        .await;
+        let source = source(&base);
+
+        source.fetch(&AREA).await.unwrap();
+        source.fetch(&AREA).await.unwrap();
+        assert_eq!(token_requests(&log), 2);
+    }
+
+    #[tokio::test]
+    async fn test_antimeridian_area_queries_both_boxes() {
+        let (base, log) = stand_in(Script {
+            token_lifetime: 1800,
+            exhausted: false,
+        })
+        .await;
+        let source = source(&base);
+
+        let area = SearchArea {
+            lat: 52.3,
+            lon: 179.9,
+            radius_km: 50.0,
+        };
+        // The stand-in's only aircraft is over Amsterdam, inside neither box's
+        // circle, so post-filtering drops it.
+        assert!(source.fetch(&area).await.unwrap().is_empty());
+
+        let queries: Vec<String> = log
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.starts_with("GET /states/all"))
+            .cloned()
+            .collect();
+        assert_eq!(queries.len(), 2);
+        assert!(queries[0].contains("lomax=180"));
+        assert!(queries[1].contains("lomin=-180"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_response() {
+        let (base, _log) = stand_in(Script {
+            token_lifetime: 1800,
+            exhausted: true,
+        })
+        .await;
+        let source = source(&base);
+
+        let err = source.fetch(&AREA).await.unwrap_err();
+        let limited = err.downcast_ref::<RateLimited>().unwrap();
+        assert_eq!(limited.retry_after, Some(Duration::from_secs(120)));
+
+        let limit = source.rate_limit().unwrap();
+        assert_eq!(limit.remaining, Some(0));
+        assert_eq!(limit.retry_after, Some(Duration::from_secs(120)));
+        assert!(matches!(source.health(), SourceHealth::Error(_)));
+    }
+}
@@ -0,0 +1,254 @@
+//! SBS-1 / BaseStation (port 30003) streaming flight source.
+//!
+//! dump1090, readsb and most receiver software emit one CSV line per decoded
+//! Mode-S message on TCP port 30003. Each `MSG` line carries only the fields
+//! of its transmission type (1–8), so [`SbsDecoder`] merges lines into an
+//! [`AircraftTable`] and a [`StreamSource`](super::stream::StreamSource)
+//! publishes snapshots at the configured cadence.
+
+use super::state::AircraftTable;
+use super::stream::StreamDecoder;
+use super::{FEET_TO_METERS, FPM_TO_MPS, KNOTS_TO_MPS};
+
+/// One parsed `MSG` line. Fields absent from the line's transmission type are
+/// `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SbsMessage {
+    /// Transmission type, 1–8.
+    pub transmission_type: u8,
+    /// ICAO24 address, lowercase hex.
+    pub icao24: String,
+    pub callsign: Option<String>,
+    /// Altitude in feet.
+    pub altitude_ft: Option<f64>,
+    /// Ground speed in knots.
+    pub ground_speed_kt: Option<f64>,
+    /// Track over ground in degrees.
+    pub track: Option<f64>,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    /// Vertical rate in feet per minute.
+    pub vertical_rate_fpm: Option<f64>,
+    pub squawk: Option<String>,
+    /// Squawk has changed.
+    pub alert: Option<bool>,
+    /// Emergency code has been set.
+    pub emergency: Option<bool>,
+    /// Transponder ident (SPI) active.
+    pub spi: Option<bool>,
+    pub on_ground: Option<bool>,
+}
+
+impl SbsMessage {
+    /// Parses one line of BaseStation output.
+    ///
+    /// Returns `None` for non-`MSG` records (`SEL`, `ID`, `AIR`, `STA`, `CLK`),
+    /// unknown transmission types and lines without a hex ident.
+    pub fn parse(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.trim().split(',').map(str::trim).collect();
+        if fields.len() < 11 || fields[0] != "MSG" {
+            return None;
+        }
+
+        let transmission_type = fields[1]
+            .parse::<u8>()
+            .ok()
+            .filter(|t| (1..=8).contains(t))?;
+        let icao24 = fields[4].to_lowercase();
+        if icao24.is_empty() {
+            return None;
+        }
+
+        let text = |i: usize| {
+            fields
+                .get(i)
+                .map(|s| s.to_string())
+                .filter(|s| !s.is_empty())
+        };
+        let number = |i: usize| fields.get(i).and_then(|s| s.parse::<f64>().ok());
+        // Flags are "0" for off and "-1" (some decoders: "1") for on.
+        let flag = |i: usize| match fields.get(i).copied() {
+            Some("-1") | Some("1") => Some(true),
+            Some("0") => Some(false),
+            _ => None,
+        };
+
+        Some(Self {
+            transmission_type,
+            icao24,
+            callsign: text(10),
+            altitude_ft: number(11),
+            ground_speed_kt: number(12),
+            track: number(13),
+            lat: number(14),
+            lon: number(15),
+            vertical_rate_fpm: number(16),
+            squawk: text(17),
+            alert: flag(18),
+            emergency: flag(19),
+            spi: flag(20),
+            on_ground: flag(21),
+        })
+    }
+
+    /// Merges this message into `table`, stamping the aircraft with `now`.
+    pub fn apply(&self, table: &mut AircraftTable, now: i64) {
+        let entry = table.update(&self.icao24, now);
+        let flight = &mut entry.flight;
+
+        if let Some(ref callsign) = self.callsign {
+            flight.callsign = callsign.clone();
+        }
+        if let Some(alt) = self.altitude_ft {
+            flight.altitude = (alt * FEET_TO_METERS) as f32;
+        }
+        if let Some(gs) = self.ground_speed_kt {
+            flight.velocity = (gs * KNOTS_TO_MPS) as f32;
+        }
+        if let Some(track) = self.track {
+            flight.true_track = track as f32;
+        }
+        if let Some(vr) = self.vertical_rate_fpm {
+            flight.vertical_rate = vr * FPM_TO_MPS;
+        }
+        if let Some(ref squawk) = self.squawk {
+            flight.squawk = Some(squawk.clone());
+        }
+        if let Some(spi) = self.spi {
+            flight.spi = spi;
+        }
+        if let Some(on_ground) = self.on_ground {
+            flight.on_ground = on_ground;
+        }
+        if let (Some(lat), Some(lon)) = (self.lat, self.lon) {
+            entry.set_position(lat, lon, now);
+        }
+    }
+}
+
+/// Splits a BaseStation byte stream into lines and applies each `MSG` line.
+#[derive(Debug, Clone, Default)]
+pub struct SbsDecoder {
+    pending: Vec<u8>,
+}
+
+impl StreamDecoder for SbsDecoder {
+    fn feed(&mut self, bytes: &[u8], table: &mut AircraftTable, now: i64) {
+        self.pending.extend_from_slice(bytes);
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            if let Some(msg) = SbsMessage::parse(&String::from_utf8_lossy(&line)) {
+                msg.apply(table, now);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::stream::StreamSource;
+    use crate::api::{FlightSource, SearchArea, SourceHealth};
+    use std::time::Duration;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    const CAPTURE: &str = "\
+MSG,1,1,1,A1B2C3,1,2024/05/01,12:00:00.000,2024/05/01,12:00:00.000,UAL123  ,,,,,,,,,,,0
+MSG,3,1,1,A1B2C3,1,2024/05/01,12:00:00.100,2024/05/01,12:00:00.100,,35000,,,37.70000,-122.40000,,,0,0,0,0
+MSG,4,1,1,A1B2C3,1,2024/05/01,12:00:00.200,2024/05/01,12:00:00.200,,,450,270.5,,,-1024,,,,,0
+MSG,6,1,1,A1B2C3,1,2024/05/01,12:00:00.300,2024/05/01,12:00:00.300,,35000,,,,,,7700,-1,-1,0,0
+STA,,1,1,A1B2C3,1,2024/05/01,12:00:00.400,2024/05/01,12:00:00.400,RM
+MSG,5,1,1,ABCDEF,1,2024/05/01,12:00:00.500,2024/05/01,12:00:00.500,,12000,,,,,,,0,,0,0
+";
+
+    #[test]
+    fn test_parse_message_types() {
+        let lines: Vec<&str> = CAPTURE.lines().collect();
+
+        let ident = SbsMessage::parse(lines[0]).unwrap();
+        assert_eq!(ident.transmission_type, 1);
+        assert_eq!(ident.icao24, "a1b2c3");
+        assert_eq!(ident.callsign.as_deref(), Some("UAL123"));
+
+        let pos = SbsMessage::parse(lines[1]).unwrap();
+        assert_eq!(pos.altitude_ft, Some(35000.0));
+        assert_eq!(pos.lat, Some(37.7));
+        assert_eq!(pos.on_ground, Some(false));
+
+        let ident_reply = SbsMessage::parse(lines[3]).unwrap();
+        assert_eq!(ident_reply.squawk.as_deref(), Some("7700"));
+        assert_eq!(ident_reply.emergency, Some(true));
+
+        assert!(SbsMessage::parse(lines[4]).is_none());
+        assert!(SbsMessage::parse("MSG,9,1,1,A1B2C3,1").is_none());
+    }
+
+    #[test]
+    fn test_partial_messages_merge() {
+        let mut table = AircraftTable::new();
+        // Split mid-line to exercise the decoder's line buffering.
+        let (head, tail) = CAPTURE.as_bytes().split_at(100);
+        let mut decoder = SbsDecoder::default();
+        decoder.feed(head, &mut table, 1_700_000_000);
+        decoder.feed(tail, &mut table, 1_700_000_000);
+        assert_eq!(table.len(), 2);
+
+        let area = SearchArea {
+            lat: 37.7,
+            lon: -122.4,
+            radius_km: 50.0,
+        };
+        let flights = table.snapshot(&area, 1_700_000_010);
+        // ABCDEF never reported a position.
+        assert_eq!(flights.len(), 1);
+        let f = &flights[0];
+        assert_eq!(f.callsign, "UAL123");
+        assert!((f.altitude - 10668.0).abs() < 1.0);
+        assert!((f.velocity - 231.5).abs() < 0.1);
+        assert_eq!(f.true_track, 270.5);
+        assert_eq!(f.squawk.as_deref(), Some("7700"));
+
+        // Everything expires once stale.
+        assert!(table.snapshot(&area, 1_700_001_000).is_empty());
+        assert!(table.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replay_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(CAPTURE.as_bytes()).await.unwrap();
+            // Keep the connection open so health stays Online.
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let source = StreamSource::new(
+            "sbs",
+            &address,
+            Duration::from_millis(100),
+            SbsDecoder::default(),
+        );
+        let area = SearchArea {
+            lat: 37.7,
+            lon: -122.4,
+            radius_km: 50.0,
+        };
+
+        let mut flights = Vec::new();
+        for _ in 0..50 {
+            flights = source.fetch(&area).await.unwrap();
+            if flights.first().is_some_and(|f| f.squawk.is_some()) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        assert_eq!(flights.len(), 1);
+        assert_eq!(flights[0].callsign, "UAL123");
+        assert_eq!(source.health(), SourceHealth::Online);
+        assert_eq!(source.poll_interval(), Some(Duration::from_millis(100)));
+    }
+}
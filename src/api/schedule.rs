@@ -0,0 +1,188 @@
+//! Adaptive poll scheduling.
+//!
+//! [`PollScheduler`] decides how long a poller waits after each fetch:
+//! - a refused request (HTTP 429) waits at least the server's Retry-After;
+//! - other failures back off exponentially, with jitter so several pollers
+//!   do not retry in lockstep;
+//! - successful polls run at the base interval, slower when no aircraft are
+//!   in range and faster when the sky is busy.
+
+use crate::events::Event;
+use std::time::{Duration, Instant};
+
+/// Aircraft count at or above which polling speeds up.
+pub const BUSY_AIRCRAFT: usize = 25;
+
+/// Slow-down factor applied while no aircraft are in range.
+const IDLE_FACTOR: u32 = 2;
+/// Speed-up divisor applied while at least [`BUSY_AIRCRAFT`] are in range.
+const BUSY_DIVISOR: u32 = 2;
+/// Upper bound on the error backoff.
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+
+/// What happened on the most recent poll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollOutcome {
+    /// The fetch succeeded and returned `aircraft` flights.
+    Success { aircraft: usize },
+    /// The source refused the request for lack of credits.
+    RateLimited { retry_after: Option<Duration> },
+    /// The fetch failed for any other reason (timeout, HTTP error, parse error).
+    Failed,
+}
+
+impl PollOutcome {
+    /// Classifies an [`Event::FlightUpdate`]. A failed update whose source
+    /// reported a Retry-After is treated as rate limited.
+    pub fn of(event: &Event) -> Self {
+        match event {
+            Event::FlightUpdate {
+                is_success: true,
+                flights,
+                ..
+            } => PollOutcome::Success {
+                aircraft: flights.len(),
+            },
+            Event::FlightUpdate { rate_limit, .. } => {
+                match rate_limit.and_then(|limit| limit.retry_after) {
+                    Some(retry_after) => PollOutcome::RateLimited {
+                        retry_after: Some(retry_after),
+                    },
+                    None => PollOutcome::Failed,
+                }
+            }
+            _ => PollOutcome::Failed,
+        }
+    }
+}
+
+/// Computes the delay before each poll of one source.
+#[derive(Debug, Clone)]
+pub struct PollScheduler {
+    base: Duration,
+    /// Consecutive failed or rate-limited polls.
+    failures: u32,
+}
+
+impl PollScheduler {
+    /// Creates a scheduler around the source's normal cadence.
+    pub fn new(base: Duration) -> Self {
+        Self { base, failures: 0 }
+    }
+
+    /// Number of consecutive failed polls.
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// Delay before the next poll after `outcome`.
+    ///
+    /// `jitter` is a random fraction in `[0, 1)`; error backoffs are spread
+    /// over the upper half of their nominal value ("equal jitter").
+    pub fn next_delay(&mut self, outcome: PollOutcome, jitter: f64) -> Duration {
+        match outcome {
+            PollOutcome::Success { aircraft } => {
+                self.failures = 0;
+                if aircraft == 0 {
+                    self.base * IDLE_FACTOR
+                } else if aircraft >= BUSY_AIRCRAFT {
+                    self.base / BUSY_DIVISOR
+                } else {
+                    self.base
+                }
+            }
+            PollOutcome::RateLimited {
+                retry_after: Some(retry_after),
+            } => {
+                self.failures += 1;
+                retry_after.max(self.base)
+            }
+            PollOutcome::RateLimited { retry_after: None } | PollOutcome::Failed => {
+                self.failures += 1;
+                let backoff = self
+                    .base
+                    .saturating_mul(1 << self.failures.min(16))
+                    .min(MAX_BACKOFF)
+                    .max(self.base);
+                backoff / 2 + backoff.mul_f64(jitter.clamp(0.0, 1.0) / 2.0)
+            }
+        }
+    }
+}
+
+/// Effective cadence of one poller, reported to the UI after every poll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollSchedule {
+    /// Delay chosen after the most recent poll.
+    pub interval: Duration,
+    /// When the next poll will start.
+    pub next_poll: Instant,
+}
+
+/// Random fraction in `[0, 1)` for [`PollScheduler::next_delay`].
+pub fn jitter() -> f64 {
+    fastrand::f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn test_adapts_to_traffic() {
+        let mut s = PollScheduler::new(BASE);
+        assert_eq!(
+            s.next_delay(PollOutcome::Success { aircraft: 0 }, 0.5),
+            BASE * 2
+        );
+        assert_eq!(
+            s.next_delay(PollOutcome::Success { aircraft: 5 }, 0.5),
+            BASE
+        );
+        assert_eq!(
+            s.next_delay(PollOutcome::Success { aircraft: 40 }, 0.5),
+            BASE / 2
+        );
+    }
+
+    #[test]
+    fn test_backoff_grows_and_resets() {
+        let mut s = PollScheduler::new(BASE);
+        let first = s.next_delay(PollOutcome::Failed, 0.0);
+        let second = s.next_delay(PollOutcome::Failed, 0.0);
+        assert_eq!(first, Duration::from_secs(30));
+        assert_eq!(second, Duration::from_secs(60));
+
+        // Jitter stays within [backoff/2, backoff].
+        let third = s.next_delay(PollOutcome::Failed, 0.999);
+        assert!(third > Duration::from_secs(230) && third <= Duration::from_secs(240));
+
+        for _ in 0..20 {
+            assert!(s.next_delay(PollOutcome::Failed, 0.999) <= MAX_BACKOFF);
+        }
+
+        s.next_delay(PollOutcome::Success { aircraft: 3 }, 0.0);
+        assert_eq!(s.failures(), 0);
+        assert_eq!(
+            s.next_delay(PollOutcome::Failed, 0.0),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn test_honours_retry_after() {
+        let mut s = PollScheduler::new(BASE);
+        let long = PollOutcome::RateLimited {
+            retry_after: Some(Duration::from_secs(900)),
+        };
+        assert_eq!(s.next_delay(long, 0.0), Duration::from_secs(900));
+
+        // Never polls faster than the base interval, even if told it may.
+        let short = PollOutcome::RateLimited {
+            retry_after: Some(Duration::from_secs(1)),
+        };
+        assert_eq!(s.next_delay(short, 0.0), BASE);
+    }
+}
@@ -0,0 +1,132 @@
+//! Per-aircraft state table for streaming receiver feeds.
+//!
+//! Streaming protocols (SBS-1, Beast, AVR) deliver partial messages: one
+//! carries the callsign, another the position, another the velocity. The
+//! [`AircraftTable`] merges them by ICAO24 into complete [`Flight`] records
+//! that a [`FlightSource`](super::FlightSource) can hand to the poller.
+
+use super::modes::CprFrame;
+use super::SearchArea;
+use crate::models::Flight;
+use std::collections::HashMap;
+
+/// Aircraft not heard from for this many seconds are dropped from the table.
+pub const STALE_AFTER_SECS: i64 = 60;
+
+/// One aircraft being assembled from partial messages.
+#[derive(Debug, Clone, Default)]
+pub struct TrackedAircraft {
+    /// Merged state; `last_contact` is updated on every message.
+    pub flight: Flight,
+    /// Unix time (seconds) of the last position update, if any.
+    pub position_time: Option<i64>,
+    /// Most recent even-format CPR position report (Mode-S feeds only).
+    pub cpr_even: Option<CprFrame>,
+    /// Most recent odd-format CPR position report (Mode-S feeds only).
+    pub cpr_odd: Option<CprFrame>,
+}
+
+impl TrackedAircraft {
+    /// Records a new position fix received at `now`.
+    pub fn set_position(&mut self, lat: f64, lon: f64, now: i64) {
+        self.flight.latitude = lat;
+        self.flight.longitude = lon;
+        self.position_time = Some(now);
+        self.flight.time_position = Some(now);
+    }
+}
+
+/// Aircraft state keyed by lowercase ICAO24.
+#[derive(Debug)]
+pub struct AircraftTable {
+    entries: HashMap<String, TrackedAircraft>,
+    /// Receiver position `(lat, lon)`, used as the reference for local CPR
+    /// decoding when an aircraft has no recent position of its own.
+    pub reference: Option<(f64, f64)>,
+    /// Seconds of silence after which an aircraft is dropped; `None` keeps
+    /// everything (used when replaying captures).
+    stale_after_secs: Option<i64>,
+}
+
+impl Default for AircraftTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AircraftTable {
+    /// Creates a table that expires aircraft after [`STALE_AFTER_SECS`].
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            reference: None,
+            stale_after_secs: Some(STALE_AFTER_SECS),
+        }
+    }
+
+    /// Creates a table that never expires aircraft, for replayed captures
+    /// whose message times have nothing to do with the wall clock.
+    pub fn without_expiry() -> Self {
+        Self {
+            stale_after_secs: None,
+            ..Self::new()
+        }
+    }
+
+    /// Returns the entry for `icao24`, creating it if needed, and marks it as
+    /// heard at `now` (unix seconds).
+    pub fn update(&mut self, icao24: &str, now: i64) -> &mut TrackedAircraft {
+        let key = icao24.trim().to_lowercase();
+        let entry = self
+            .entries
+            .entry(key.clone())
+            .or_insert_with(|| TrackedAircraft {
+                flight: Flight {
+                    icao24: key,
+                    callsign: "N/A".to_string(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+        entry.flight.last_contact = Some(now);
+        entry
+    }
+
+    /// Looks up an aircraft without touching its last-contact time.
+    pub fn get(&self, icao24: &str) -> Option<&TrackedAircraft> {
+        self.entries.get(&icao24.trim().to_lowercase())
+    }
+
+    /// Whether `icao24` has been heard from and not yet expired.
+    pub fn contains(&self, icao24: &str) -> bool {
+        self.get(icao24).is_some()
+    }
+
+    /// Number of aircraft currently tracked, with or without a position.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drops stale aircraft (unless expiry is disabled) and returns those
+    /// with a known position inside `area`.
+    pub fn snapshot(&mut self, area: &SearchArea, now: i64) -> Vec<Flight> {
+        if let Some(stale_after) = self.stale_after_secs {
+            self.entries.retain(|_, a| {
+                a.flight
+                    .last_contact
+                    .is_some_and(|t| now - t <= stale_after)
+            });
+        }
+
+        self.entries
+            .values()
+            .filter(|a| a.position_time.is_some())
+            .filter(|a| area.contains(&a.flight))
+            .map(|a| a.flight.clone())
+            .collect()
+    }
+}
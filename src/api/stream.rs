@@ -0,0 +1,299 @@
+//! Generic streaming flight source.
+//!
+//! Receiver feeds such as SBS-1 (port 30003), Beast (port 30005) and AVR
+//! (port 30002) push messages continuously rather than answering requests.
+//! [`StreamSource`] owns the connection (or capture file, or stdin), hands
+//! every chunk of bytes to a protocol-specific
+//! [`StreamDecoder`] that merges messages into an [`AircraftTable`], and lets
+//! the poller snapshot that table at the configured cadence.
+
+use super::state::AircraftTable;
+use super::{FlightSource, SearchArea, SourceHealth};
+use crate::models::Flight;
+use async_trait::async_trait;
+use color_eyre::eyre::{eyre, Result};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::net::TcpStream;
+use tracing::{info, warn};
+
+/// Delay before reconnecting after the feed drops or refuses a connection.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Protocol decoder for a byte stream.
+///
+/// A fresh clone of the decoder passed to [`StreamSource::new`] is used for
+/// every connection, so any framing buffer starts empty after a reconnect.
+pub trait StreamDecoder: Clone + Send + Sync + 'static {
+    /// Consumes `bytes` and applies every complete message to `table`,
+    /// stamping updated aircraft with `now` (unix seconds). Incomplete
+    /// trailing data is kept until the next call.
+    fn feed(&mut self, bytes: &[u8], table: &mut AircraftTable, now: i64);
+
+    /// Called once before a recording (file or stdin) is read. Decoders for
+    /// formats that carry receiver timestamps then stamp messages by those,
+    /// so a capture read in seconds keeps its original time spacing; the
+    /// default keeps stamping with `now`.
+    fn start_replay(&mut self) {}
+}
+
+/// Where a [`StreamSource`] reads its bytes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamInput {
+    /// Live TCP feed at `host:port`; reconnects when dropped.
+    Tcp(String),
+    /// Capture file, read once to the end.
+    File(PathBuf),
+    /// Standard input, read once to the end (e.g. `rtl_adsb | redwood-tui`).
+    Stdin,
+}
+
+impl StreamInput {
+    /// Whether the input is a finite recording rather than a live feed.
+    fn is_replay(&self) -> bool {
+        !matches!(self, StreamInput::Tcp(_))
+    }
+}
+
+/// A [`FlightSource`] backed by a long-lived byte stream.
+///
+/// The reader task is started on the first [`fetch`](FlightSource::fetch) and,
+/// for TCP feeds, reconnects on its own; `fetch` only snapshots the table.
+pub struct StreamSource<D: StreamDecoder> {
+    name: &'static str,
+    input: StreamInput,
+    update_interval: Duration,
+    decoder: D,
+    table: Arc<Mutex<AircraftTable>>,
+    health: Arc<Mutex<SourceHealth>>,
+    reader: OnceLock<tokio::task::JoinHandle<()>>,
+}
+
+impl<D: StreamDecoder> StreamSource<D> {
+    /// Creates a source named `name` reading from the TCP feed at `address`
+    /// (e.g. `"192.168.1.20:30003"`) that publishes snapshots every
+    /// `update_interval`.
+    pub fn new(name: &'static str, address: &str, update_interval: Duration, decoder: D) -> Self {
+        Self::with_input(
+            name,
+            StreamInput::Tcp(address.to_string()),
+            update_interval,
+            decoder,
+        )
+    }
+
+    /// Creates a source reading from any [`StreamInput`]. Replayed inputs
+    /// keep every aircraft instead of expiring them, since their messages
+    /// are stamped relative to load time rather than as they arrive.
+    pub fn with_input(
+        name: &'static str,
+        input: StreamInput,
+        update_interval: Duration,
+        decoder: D,
+    ) -> Self {
+        let table = if input.is_replay() {
+            AircraftTable::without_expiry()
+        } else {
+            AircraftTable::new()
+        };
+        Self {
+            name,
+            input,
+            update_interval,
+            decoder,
+            table: Arc::new(Mutex::new(table)),
+            health: Arc::new(Mutex::new(SourceHealth::Pending)),
+            reader: OnceLock::new(),
+        }
+    }
+
+    fn ensure_reader(&self) {
+        self.reader.get_or_init(|| {
+            tokio::spawn(run_reader(
+                self.input.clone(),
+                self.decoder.clone(),
+                Arc::clone(&self.table),
+                Arc::clone(&self.health),
+            ))
+        });
+    }
+}
+
+impl<D: StreamDecoder> Drop for StreamSource<D> {
+    fn drop(&mut self) {
+        if let Some(reader) = self.reader.get() {
+            reader.abort();
+        }
+    }
+}
+
+/// Feeds everything `reader` yields into `decoder` until EOF or an error.
+async fn pump<R: AsyncRead + Unpin, D: StreamDecoder>(
+    mut reader: R,
+    decoder: &mut D,
+    table: &Mutex<AircraftTable>,
+) -> std::io::Result<()> {
+    let mut buf = vec![0u8; 16 * 1024];
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        if let Ok(mut table) = table.lock() {
+            decoder.feed(&buf[..n], &mut table, chrono::Utc::now().timestamp());
+        }
+    }
+}
+
+/// Reads `input` into the table. TCP feeds are retried after
+/// [`RECONNECT_DELAY`] whenever they drop and run until aborted; files and
+/// stdin are read once.
+async fn run_reader<D: StreamDecoder>(
+    input: StreamInput,
+    decoder: D,
+    table: Arc<Mutex<AircraftTable>>,
+    health: Arc<Mutex<SourceHealth>>,
+) {
+    let set_health = |h: SourceHealth| {
+        if let Ok(mut cell) = health.lock() {
+            *cell = h;
+        }
+    };
+
+    let address = match input {
+        StreamInput::Tcp(address) => address,
+        StreamInput::File(path) => {
+            let mut decoder = decoder;
+            decoder.start_replay();
+            let result = match tokio::fs::File::open(&path).await {
+                Ok(file) => pump(file, &mut decoder, &table).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => {
+                    info!(path = %path.display(), "finished reading capture file");
+                    set_health(SourceHealth::Online);
+                }
+                Err(e) => {
+                    warn!(path = %path.display(), error = %e, "capture file read failed");
+                    set_health(SourceHealth::Error(e.to_string()));
+                }
+            }
+            return;
+        }
+        StreamInput::Stdin => {
+            set_health(SourceHealth::Online);
+            let mut decoder = decoder;
+            decoder.start_replay();
+            if let Err(e) = pump(tokio::io::stdin(), &mut decoder, &table).await {
+                warn!(error = %e, "stdin read failed");
+                set_health(SourceHealth::Error(e.to_string()));
+            }
+            return;
+        }
+    };
+
+    loop {
+        match TcpStream::connect(&address).await {
+            Ok(stream) => {
+                info!(address = %address, "connected to receiver feed");
+                set_health(SourceHealth::Online);
+                match pump(stream, &mut decoder.clone(), &table).await {
+                    Ok(()) => {
+                        warn!(address = %address, "receiver feed closed the connection");
+                        set_health(SourceHealth::Error("connection closed".to_string()));
+                    }
+                    Err(e) => {
+                        warn!(address = %address, error = %e, "receiver feed read failed");
+                        set_health(SourceHealth::Error(e.to_string()));
+                    }
+                }
+            }
+            Err(e) => {
+                warn!(address = %address, error = %e, "receiver feed connection failed");
+                set_health(SourceHealth::Error(e.to_string()));
+            }
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+#[async_trait]
+impl<D: StreamDecoder> FlightSource for StreamSource<D> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn fetch(&self, area: &SearchArea) -> Result<Vec<Flight>> {
+        self.ensure_reader();
+        // A dropped feed is a failed fetch, so the poller backs off and the
+        // dashboard shows the error instead of an empty sky.
+        if let SourceHealth::Error(e) = self.health() {
+            return Err(eyre!("{} feed unavailable: {}", self.name, e));
+        }
+        let now = chrono::Utc::now().timestamp();
+        let flights = self
+            .table
+            .lock()
+            .map(|mut t| {
+                t.reference = Some((area.lat, area.lon));
+                t.snapshot(area, now)
+            })
+            .unwrap_or_default();
+        Ok(flights)
+    }
+
+    fn health(&self) -> SourceHealth {
+        self.health.lock().map(|h| h.clone()).unwrap_or_default()
+    }
+
+    fn poll_interval(&self) -> Option<Duration> {
+        Some(self.update_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct NullDecoder;
+
+    impl StreamDecoder for NullDecoder {
+        fn feed(&mut self, _: &[u8], _: &mut AircraftTable, _: i64) {}
+    }
+
+    #[tokio::test]
+    async fn test_refused_connection_is_an_error() {
+        // Bind and drop a listener to get a port nothing listens on.
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let source = StreamSource::new(
+            "test",
+            &format!("127.0.0.1:{}", port),
+            Duration::from_millis(100),
+            NullDecoder,
+        );
+        let area = SearchArea {
+            lat: 0.0,
+            lon: 0.0,
+            radius_km: 10.0,
+        };
+
+        let mut result = source.fetch(&area).await;
+        for _ in 0..50 {
+            if result.is_err() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            result = source.fetch(&area).await;
+        }
+        assert!(result.is_err(), "refused feed reported {:?}", result);
+        assert!(matches!(source.health(), SourceHealth::Error(_)));
+    }
+}
@@ -5,11 +5,50 @@
 //! user input and periodic tick updates. It coordinates with the main event loop
 //! in `main.rs` and the database intialization worker in `db.rs`.
 
-use crate::config::Config;
+use crate::alerts::{AlertKind, AlertLog};
+use crate::api::schedule::PollSchedule;
+use crate::api::{RateLimit, SourceHealth};
+use crate::classify::AircraftClass;
+use crate::config::{Config, UiConfig};
+use crate::db::{
+    check_database, migrate_database, newest_csv, DbStatus, ImportMode, SharedRegistry, DATA_DIR,
+    DB_PATH,
+};
 use crate::events::Event;
-use crate::models::Flight;
+use crate::merge::FlightMerger;
+use crate::models::{ClosestApproach, Flight, Observer};
+use crate::phase::{FlightPhase, PhaseFilter, PhaseTracker};
+use crate::tracks::TrackStore;
+use crate::units::Units;
 use crossterm::event::{KeyCode, KeyEvent};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 use std::sync::mpsc;
+use std::time::Instant;
+use tracing::warn;
+
+/// Positions are not projected further than this past the last report;
+/// beyond it the aircraft is more likely gone than still flying straight.
+const MAX_EXTRAPOLATION_SECS: f64 = 120.0;
+/// Time over which the gap between an estimated position and the next real
+/// report is closed.
+const SNAP_BACK_SECS: f64 = 2.0;
+
+/// Offset still applied to an aircraft's projected position after a real
+/// report arrived, fading to zero over [`SNAP_BACK_SECS`].
+#[derive(Debug, Clone, Copy)]
+struct SnapBack {
+    /// (latitude °, longitude °, altitude m) of the displayed position
+    /// relative to the new projection when the report arrived.
+    offset: (f64, f64, f32),
+    /// Unix time (fractional seconds) the report arrived.
+    start: f64,
+}
+
+/// Current Unix time in fractional seconds.
+fn unix_now() -> f64 {
+    chrono::Utc::now().timestamp_millis() as f64 / 1000.0
+}
 
 /// Messages sent during first-run DB initialization.
 ///
@@ -42,6 +81,29 @@ pub enum ViewMode {
     Spotter,
     /// Settings: screen for configuring app settings (not implemented yet).
     Settings,
+    /// Alerts: active and past emergency squawk / ident alerts.
+    Alerts,
+}
+
+/// Order of the flight list.
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub enum SortMode {
+    /// Nearest to farthest by current ground distance.
+    #[default]
+    Distance,
+    /// Aircraft about to pass overhead first, soonest first; then the rest
+    /// by predicted closest-approach distance.
+    ClosestApproach,
+}
+
+impl SortMode {
+    /// Short label for the UI.
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Distance => "distance",
+            SortMode::ClosestApproach => "closest approach",
+        }
+    }
 }
 
 /// Main application state and controller.
@@ -59,6 +121,8 @@ pub struct App {
     pub flights: Vec<Flight>,
     /// Index of the selected flight in the flights list.
     pub selected_index: usize,
+    /// How `flights` is ordered.
+    pub sort_mode: SortMode,
     /// Number of tick events processed; used for periodic UI updates.
     pub tick_count: usize,
     /// When "true", the main loop exits.
@@ -72,6 +136,13 @@ pub struct App {
     pub init_message: String,
     /// channel to receive initialization messages from the DB worker.
     pub init_rx: Option<mpsc::Receiver<crate::events::Event>>,
+    /// Aircraft DB state as last checked, at startup or after a build.
+    pub db_status: DbStatus,
+    /// Why the existing DB looks incomplete; while set, the user is asked
+    /// whether to rebuild it.
+    pub rebuild_prompt: Option<String>,
+    /// Registry shared with the pollers; reset after the DB is rebuilt.
+    pub registry: SharedRegistry,
 
     /// Timestamp of the last successful flight update.
     pub last_update: Option<std::time::Instant>,
@@ -79,10 +150,30 @@ pub struct App {
     pub last_update_success: bool,
     /// Number of flights in the current set that were enriched with DB data.
     pub db_match_count: usize,
+    /// Health of each configured flight source, keyed by source name.
+    pub source_health: BTreeMap<String, SourceHealth>,
+    /// API credit state of each rate-limited source, keyed by source name.
+    pub source_rate_limits: BTreeMap<String, RateLimit>,
+    /// Effective interval and next poll time of each source's poller.
+    pub poll_schedules: BTreeMap<String, PollSchedule>,
+    /// Merges updates from all sources into `flights`.
+    pub merger: FlightMerger,
+    /// Position history of every aircraft seen recently.
+    pub tracks: TrackStore,
+    /// Pending snap-back corrections, keyed by ICAO24.
+    snap_back: HashMap<String, SnapBack>,
+    /// Debounced flight phase of every aircraft.
+    pub phases: PhaseTracker,
+    /// Emergency squawk and ident alerts, open and past.
+    pub alerts: AlertLog,
+    /// Set when an alert is raised; the main loop rings the terminal bell
+    /// and clears it.
+    pub bell_pending: bool,
 
     /// Loaded configuration; used by Settings view and saved to config.toml on Save.
     pub config: Config,
-    /// Index of the selected setting row in the Settings view (0..=5).
+    /// Index of the selected setting row in the Settings view
+    /// (`0..Self::SETTINGS_FIELD_COUNT`).
     pub settings_selected_index: usize,
     /// Brief message shown in Settings after save (e.g. "Config saved.").
     pub settings_message: Option<String>,
@@ -93,19 +184,30 @@ pub struct App {
 impl App {
     /// Creates a new application instance.
     ///
-    /// If `opensky_aircraft.db` does not exist, starts database initialization
-    /// in a background thread and sets `is_initializing` to `true` and
-    /// `init_rx` to the receiver for progress/done/error events. Otherwise
-    /// the app starts in a ready state with no init receiver.
+    /// Upgrades `opensky_aircraft.db` to the current schema (see
+    /// [`migrate_database`]) and checks it (see [`check_database`]). If it does
+    /// not exist, starts database initialization in a background thread and
+    /// sets `is_initializing` to `true` and `init_rx` to the receiver for
+    /// progress/done/error events. If it exists but is incomplete or was
+    /// built for another schema, `rebuild_prompt` asks whether to rebuild
+    /// it. Otherwise the app starts in a ready state with no init receiver.
     ///
     /// # Panics
     ///
     /// Does not panic. Database init failures are reported via `Event::DbError`.
     pub fn new() -> Self {
-        let db_exists = std::path::Path::new("opensky_aircraft.db").exists();
-        let (is_initializing, init_rx) = if !db_exists {
+        // A failed upgrade shows up below as a schema mismatch.
+        if let Err(e) = migrate_database(Path::new(DB_PATH)) {
+            warn!("{}", e);
+        }
+        let db_status = check_database(Path::new(DB_PATH));
+        let rebuild_prompt = match &db_status {
+            DbStatus::Invalid(reason) => Some(reason.clone()),
+            _ => None,
+        };
+        let (is_initializing, init_rx) = if db_status == DbStatus::Missing {
             let (tx, rx) = mpsc::channel();
-            crate::db::init_database(tx); // We'll define this below
+            crate::db::init_database(tx);
             (true, Some(rx))
         } else {
             (false, None)
@@ -116,14 +218,27 @@ impl App {
             user_coords: (0.0, 0.0),
             flights: Vec::new(),
             selected_index: 0,
+            sort_mode: SortMode::Distance,
             tick_count: 0,
             should_quit: false,
             is_initializing,
             init_progress: 0.0,
             init_message: "Initializing database...".to_string(),
             init_rx,
+            db_status,
+            rebuild_prompt,
+            registry: SharedRegistry::default(),
             last_update: None,
             db_match_count: 0,
+            source_health: BTreeMap::new(),
+            source_rate_limits: BTreeMap::new(),
+            poll_schedules: BTreeMap::new(),
+            merger: FlightMerger::new(),
+            tracks: TrackStore::default(),
+            snap_back: HashMap::new(),
+            phases: PhaseTracker::default(),
+            alerts: AlertLog::default(),
+            bell_pending: false,
             last_update_success: false,
             config: Config::default(),
             settings_selected_index: 0,
@@ -132,14 +247,53 @@ impl App {
         }
     }
 
+    /// Rebuilds the aircraft DB in the background, showing the loading
+    /// screen until it is done. Does nothing if a build is running.
+    pub fn start_db_build(&mut self) {
+        if self.init_rx.is_some() {
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        crate::db::init_database(tx);
+        self.watch_db_job(rx, "Rebuilding database...".to_string());
+    }
+
+    /// Merges the newest CSV in `data/` into the aircraft DB in the
+    /// background (see [`newest_csv`]), showing the loading screen until it
+    /// is done. Does nothing if a build is running.
+    pub fn start_db_import(&mut self) {
+        if self.init_rx.is_some() {
+            return;
+        }
+        let Some(source) = newest_csv(Path::new(DATA_DIR)) else {
+            self.settings_message = Some(format!("No aircraft CSV in {}/", DATA_DIR));
+            return;
+        };
+        let (tx, rx) = mpsc::channel();
+        let message = format!("Importing {}...", source.display());
+        crate::db::import_in_background(source, ImportMode::Full, tx);
+        self.watch_db_job(rx, message);
+    }
+
+    /// Shows the loading screen for a DB build or import reporting on `rx`.
+    fn watch_db_job(&mut self, rx: mpsc::Receiver<Event>, message: String) {
+        self.init_rx = Some(rx);
+        self.is_initializing = true;
+        self.init_progress = 0.0;
+        self.init_message = message;
+        self.rebuild_prompt = None;
+    }
+
     /// Processes a single tick from the event loop.
     ///
-    /// Increments `tick_count` and drains any pending init events from the DB thread.
+    /// Increments `tick_count`, dead-reckons every flight forward (see
+    /// [`App::extrapolate_positions`]) and drains any pending init events from the DB thread.
     /// Updates `init_progress` and `init_message` accordingly.
     /// Sets `is_initializing` to `false` and `init_rx` to `None` when initialization completes.
     /// Sets `init_message` to the error message if initialization fails.
     pub fn on_tick(&mut self) {
         self.tick_count += 1;
+        self.extrapolate_positions(unix_now());
 
         let mut should_cleanup = false;
 
@@ -150,9 +304,24 @@ impl App {
                     Event::DbProgress(p) => self.init_progress = p,
                     Event::DbDone => {
                         self.is_initializing = false;
+                        self.registry.reset();
+                        self.db_status = check_database(Path::new(DB_PATH));
+                        should_cleanup = true;
+                    }
+                    Event::DbImported(report) => {
+                        self.is_initializing = false;
+                        self.registry.reset();
+                        self.db_status = check_database(Path::new(DB_PATH));
+                        self.settings_message = Some(format!("Imported: {}", report));
                         should_cleanup = true;
                     }
                     Event::DbError(e) => {
+                        // A failed rebuild or import leaves the previous DB
+                        // in place.
+                        if self.db_status != DbStatus::Missing {
+                            self.is_initializing = false;
+                            self.settings_message = Some(format!("Database update failed: {}", e));
+                        }
                         self.init_message = e;
                         should_cleanup = true;
                     }
@@ -165,6 +334,234 @@ impl App {
         }
     }
 
+    /// Applies an [`Event::FlightUpdate`] from one flight source.
+    ///
+    /// Records the source's health and credits and, on success, merges its
+    /// flights with those of the other sources (see [`FlightMerger`]),
+    /// appends them to the track history and rebuilds `flights` sorted
+    /// nearest to farthest. A failed update keeps the aircraft that source
+    /// last reported.
+    pub fn on_flight_update(
+        &mut self,
+        source: String,
+        flights: Vec<Flight>,
+        health: SourceHealth,
+        rate_limit: Option<RateLimit>,
+        is_success: bool,
+        timestamp: Instant,
+    ) {
+        self.last_update_success = is_success;
+        self.source_health.insert(source.clone(), health);
+        if let Some(rate_limit) = rate_limit {
+            self.source_rate_limits.insert(source.clone(), rate_limit);
+        }
+        if !is_success {
+            return;
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        self.merger.apply(&source, flights, now);
+        let mut sorted = self.merger.flights();
+        self.tracks.record(&sorted, now);
+        self.phases.field_elevation_m = self.config.location.observer_altitude_m;
+        self.phases
+            .update(&sorted, |icao| self.tracks.get(icao), now);
+        let military_alert = self.config.ui.military_alert;
+        for alert in self.alerts.update(&sorted, now, military_alert) {
+            warn!(
+                icao24 = %alert.icao24,
+                callsign = %alert.callsign,
+                alert = alert.kind.label(),
+                "Aircraft alert raised"
+            );
+            self.bell_pending = true;
+        }
+
+        // Remember where each aircraft was drawn so the switch to the new
+        // report is eased rather than a jump.
+        let now_f = unix_now();
+        let shown: HashMap<&str, &Flight> = self
+            .flights
+            .iter()
+            .map(|f| (f.icao24.as_str(), f))
+            .collect();
+        for flight in &sorted {
+            let Some(shown) = shown.get(flight.icao24.as_str()) else {
+                continue;
+            };
+            let (lat, lon, alt) = project_reported(flight, now_f);
+            self.snap_back.insert(
+                flight.icao24.clone(),
+                SnapBack {
+                    offset: (
+                        shown.latitude - lat,
+                        wrap_degrees(shown.longitude - lon),
+                        shown.altitude - alt,
+                    ),
+                    start: now_f,
+                },
+            );
+        }
+
+        if self.config.ui.horizon_filter {
+            let observer = self.observer();
+            let min_elevation = self.config.ui.min_elevation_deg;
+            let alerts = &self.alerts;
+            sorted.retain(|f| {
+                alerts.active_for(&f.icao24).is_some()
+                    || f.look_angles(&observer).elevation_deg >= min_elevation
+            });
+        }
+
+        if self.config.ui.military_only {
+            sorted.retain(|f| !AircraftClass::of(f).is_civil());
+        }
+
+        let phase_filter = self.config.ui.phase_filter;
+        if phase_filter != PhaseFilter::All {
+            let (alerts, phases) = (&self.alerts, &self.phases);
+            sorted.retain(|f| {
+                alerts.active_for(&f.icao24).is_some()
+                    || phase_filter.matches(phases.get(&f.icao24))
+            });
+        }
+
+        self.db_match_count = sorted.iter().filter(|f| f.registration.is_some()).count();
+        let selected = self.selected_icao24();
+        self.flights = sorted;
+        self.sort_flights(selected);
+        self.last_update = Some(timestamp);
+        self.extrapolate_positions(now_f);
+    }
+
+    /// ICAO24 of the selected flight, if any.
+    fn selected_icao24(&self) -> Option<String> {
+        self.flights
+            .get(self.selected_index)
+            .map(|f| f.icao24.clone())
+    }
+
+    /// Orders `flights` by `sort_mode`, keeping `selected` (an ICAO24)
+    /// selected if it is still in the list.
+    fn sort_flights(&mut self, selected: Option<String>) {
+        let (u_lat, u_lon) = self.user_coords;
+        match self.sort_mode {
+            SortMode::Distance => self.flights.sort_by(|a, b| {
+                a.distance_from(u_lat, u_lon)
+                    .total_cmp(&b.distance_from(u_lat, u_lon))
+            }),
+            SortMode::ClosestApproach => {
+                let ui = &self.config.ui;
+                let key = |f: &Flight| {
+                    let cpa = f.closest_approach(u_lat, u_lon);
+                    if passes_overhead(ui, &cpa) {
+                        (0, cpa.time_secs)
+                    } else {
+                        (1, cpa.distance_km)
+                    }
+                };
+                let flights = std::mem::take(&mut self.flights);
+                let mut keyed: Vec<_> = flights.into_iter().map(|f| (key(&f), f)).collect();
+                keyed.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+                self.flights = keyed.into_iter().map(|(_, f)| f).collect();
+            }
+        }
+        // Aircraft with an open alert are pinned to the top, most severe
+        // first; the sort is stable so each group keeps the order above.
+        let alerts = &self.alerts;
+        self.flights.sort_by_key(|f| {
+            let alert = alerts.active_for(&f.icao24);
+            (alert.is_none(), alert)
+        });
+        if let Some(icao) = selected {
+            if let Some(i) = self.flights.iter().position(|f| f.icao24 == icao) {
+                self.selected_index = i;
+            }
+        }
+    }
+
+    /// Whether a predicted approach comes within `overhead_radius_km` inside
+    /// the `overhead_window_minutes` horizon.
+    pub fn passes_overhead(&self, cpa: &ClosestApproach) -> bool {
+        passes_overhead(&self.config.ui, cpa)
+    }
+
+    /// Current flight phase of `flight`, once classified.
+    pub fn phase_for(&self, flight: &Flight) -> Option<FlightPhase> {
+        self.phases.get(&flight.icao24)
+    }
+
+    /// Most severe open alert for `flight`, if any.
+    pub fn alert_for(&self, flight: &Flight) -> Option<AlertKind> {
+        self.alerts.active_for(&flight.icao24)
+    }
+
+    /// Blink phase for alerting aircraft: on for 3 ticks, off for 3.
+    pub fn flash_on(&self) -> bool {
+        (self.tick_count / 3) % 2 == 0
+    }
+
+    /// Display units from the current UI settings.
+    pub fn units(&self) -> Units {
+        Units::from_config(&self.config.ui)
+    }
+
+    /// Closest approach of `flight` to the user.
+    pub fn closest_approach(&self, flight: &Flight) -> ClosestApproach {
+        flight.closest_approach(self.user_coords.0, self.user_coords.1)
+    }
+
+    /// Moves every flight in `flights` to its dead-reckoned position at
+    /// `now` (Unix seconds), projecting from the last reported state held by
+    /// the merger, and fades out any snap-back offset. Flights whose
+    /// displayed position differs from the report are marked `extrapolated`.
+    pub fn extrapolate_positions(&mut self, now: f64) {
+        self.snap_back
+            .retain(|_, snap| now - snap.start < SNAP_BACK_SECS);
+
+        for flight in &mut self.flights {
+            let Some(reported) = self.merger.get(&flight.icao24) else {
+                continue;
+            };
+            let (mut lat, mut lon, mut alt) = project_reported(reported, now);
+            if let Some(snap) = self.snap_back.get(&flight.icao24) {
+                let weight = 1.0 - (now - snap.start) / SNAP_BACK_SECS;
+                lat += snap.offset.0 * weight;
+                lon = wrap_degrees(lon + snap.offset.1 * weight);
+                alt += snap.offset.2 * weight as f32;
+            }
+            flight.extrapolated = lat != reported.latitude || lon != reported.longitude;
+            flight.latitude = lat;
+            flight.longitude = lon;
+            flight.altitude = alt;
+        }
+    }
+
+    /// The user as an observer: `user_coords` at the configured height.
+    pub fn observer(&self) -> Observer {
+        Observer {
+            latitude: self.user_coords.0,
+            longitude: self.user_coords.1,
+            altitude_m: self.config.location.observer_altitude_m,
+        }
+    }
+
+    /// Combined health of all sources: online if any source is online,
+    /// otherwise the first error, otherwise pending.
+    pub fn network_health(&self) -> SourceHealth {
+        let mut combined = SourceHealth::Pending;
+        for health in self.source_health.values() {
+            match health {
+                SourceHealth::Online => return SourceHealth::Online,
+                SourceHealth::Error(_) if combined == SourceHealth::Pending => {
+                    combined = health.clone();
+                }
+                _ => {}
+            }
+        }
+        combined
+    }
+
     /// Handles a keyboard event from the user.
     ///
     /// If the app is initializing, only allows quitting with 'q'.
@@ -186,6 +583,16 @@ impl App {
             return;
         }
 
+        if self.rebuild_prompt.is_some() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => self.start_db_build(),
+                KeyCode::Char('n') | KeyCode::Esc => self.rebuild_prompt = None,
+                KeyCode::Char('q') => self.should_quit = true,
+                _ => {}
+            }
+            return;
+        }
+
         if self.view_mode == ViewMode::Settings {
             self.handle_settings_key(key);
             return;
@@ -193,24 +600,32 @@ impl App {
 
         match key.code {
             KeyCode::Char('q') => self.should_quit = true,
-            KeyCode::Down | KeyCode::Char('j') => {
-                if !self.flights.is_empty() {
-                    self.selected_index = (self.selected_index + 1) % self.flights.len();
-                }
+            KeyCode::Down | KeyCode::Char('j') if !self.flights.is_empty() => {
+                self.selected_index = (self.selected_index + 1) % self.flights.len();
             }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if !self.flights.is_empty() {
-                    self.selected_index = self
-                        .selected_index
-                        .checked_sub(1)
-                        .unwrap_or(self.flights.len() - 1);
-                }
+            KeyCode::Up | KeyCode::Char('k') if !self.flights.is_empty() => {
+                self.selected_index = self
+                    .selected_index
+                    .checked_sub(1)
+                    .unwrap_or(self.flights.len() - 1);
             }
+            KeyCode::Char('o') => {
+                self.sort_mode = match self.sort_mode {
+                    SortMode::Distance => SortMode::ClosestApproach,
+                    SortMode::ClosestApproach => SortMode::Distance,
+                };
+                let selected = self.selected_icao24();
+                self.sort_flights(selected);
+            }
+            // Applied from the next flight update on; saved with the config.
+            KeyCode::Char('h') => self.config.ui.horizon_filter = !self.config.ui.horizon_filter,
+            KeyCode::Char('m') => self.config.ui.military_only = !self.config.ui.military_only,
+            KeyCode::Char('p') => self.config.ui.phase_filter = self.config.ui.phase_filter.next(),
             _ => {}
         }
     }
 
-    const SETTINGS_FIELD_COUNT: usize = 6;
+    const SETTINGS_FIELD_COUNT: usize = 15;
 
     /// Handles key input when the Settings view is active.
     fn handle_settings_key(&mut self, key: KeyEvent) {
@@ -258,6 +673,13 @@ impl App {
                         _ => "Dashboard".to_string(),
                     };
                 }
+                8 => self.config.ui.horizon_filter = !self.config.ui.horizon_filter,
+                9 => self.config.ui.units = self.config.ui.units.next(),
+                10 => self.config.ui.phase_filter = self.config.ui.phase_filter.next(),
+                11 => self.config.ui.military_only = !self.config.ui.military_only,
+                12 => self.config.ui.military_alert = !self.config.ui.military_alert,
+                13 => self.start_db_build(),
+                14 => self.start_db_import(),
                 _ => {}
             },
             Char('+') | Char('=') => self.settings_increment(),
@@ -282,6 +704,14 @@ impl App {
                 self.config.api.poll_interval_seconds =
                     (self.config.api.poll_interval_seconds + 5).min(600)
             }
+            6 => {
+                self.config.location.observer_altitude_m =
+                    (self.config.location.observer_altitude_m + 10.0).min(9000.0)
+            }
+            7 => {
+                self.config.ui.min_elevation_deg =
+                    (self.config.ui.min_elevation_deg + 1.0).min(90.0)
+            }
             _ => {}
         }
     }
@@ -307,7 +737,41 @@ impl App {
                     .saturating_sub(5)
                     .max(5)
             }
+            6 => {
+                self.config.location.observer_altitude_m =
+                    (self.config.location.observer_altitude_m - 10.0).max(-500.0)
+            }
+            7 => {
+                self.config.ui.min_elevation_deg =
+                    (self.config.ui.min_elevation_deg - 1.0).max(-5.0)
+            }
             _ => {}
         }
     }
 }
+
+/// Projects a reported flight to `now` (Unix seconds) from its position time,
+/// up to [`MAX_EXTRAPOLATION_SECS`]. Reports less than a second old are
+/// used as they are.
+fn project_reported(flight: &Flight, now: f64) -> (f64, f64, f32) {
+    let reported = (flight.latitude, flight.longitude, flight.altitude);
+    let Some(reported_at) = flight.time_position.or(flight.last_contact) else {
+        return reported;
+    };
+    let elapsed = (now - reported_at as f64).min(MAX_EXTRAPOLATION_SECS);
+    if elapsed < 1.0 {
+        return reported;
+    }
+    flight.project(elapsed)
+}
+
+/// Wraps a longitude or longitude difference into `[-180, 180)`.
+fn wrap_degrees(deg: f64) -> f64 {
+    (deg + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// See [`App::passes_overhead`].
+fn passes_overhead(ui: &UiConfig, cpa: &ClosestApproach) -> bool {
+    cpa.distance_km <= ui.overhead_radius_km
+        && cpa.time_secs <= ui.overhead_window_minutes as f64 * 60.0
+}
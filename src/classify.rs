@@ -0,0 +1,216 @@
+//! Military and government aircraft classification.
+//!
+//! [`AircraftClass::of`] combines three independent hints, any of which is
+//! enough: the ICAO24 address lying in a military sub-block
+//! ([`icao::lookup`]), a callsign prefix used by military or state flights
+//! (`RCH123`, `NASA504`), and an operator name from the aircraft DB
+//! ("United States Air Force", "Police Aviation").
+
+use crate::icao;
+use crate::models::Flight;
+
+/// Callsign prefixes of military flights.
+const MILITARY_CALLSIGNS: &[&str] = &[
+    "RCH",   // USAF Air Mobility Command ("Reach")
+    "REACH", // ditto, spelled out
+    "CNV",   // US Navy
+    "VV",    // US Navy
+    "PAT",   // US Army priority air transport
+    "SPAR",  // USAF special air resources
+    "SAM",   // USAF special air mission
+    "EVAC",  // USAF aeromedical evacuation
+    "DUKE",  // US Army
+    "RRR",   // Royal Air Force
+    "ASCOT", // Royal Air Force
+    "GAF",   // German Air Force
+    "CFC",   // Canadian Forces
+    "NATO",  // NATO AEW
+    "IAM",   // Italian Air Force
+    "FAF",   // French Air Force
+    "CTM",   // French Air Force transport
+    "BAF",   // Belgian Air Force
+    "NAF",   // Royal Netherlands Air Force
+    "ASY",   // Royal Australian Air Force
+    "KIWI",  // Royal New Zealand Air Force
+];
+
+/// Callsign prefixes of government (non-military state) flights.
+const GOVERNMENT_CALLSIGNS: &[&str] = &[
+    "NASA", // NASA
+    "FLC",  // FAA flight inspection
+    "CG",   // coast guard
+    "POL",  // police
+    "NPAS", // UK National Police Air Service
+];
+
+/// Operator name fragments (lowercase) of military operators.
+const MILITARY_OPERATORS: &[&str] = &[
+    "air force",
+    "navy",
+    "army",
+    "marine corps",
+    "military",
+    "national guard",
+    "luftwaffe",
+    "armee de l'air",
+    "defence",
+    "defense",
+];
+
+/// Operator name fragments (lowercase) of government operators.
+const GOVERNMENT_OPERATORS: &[&str] = &[
+    "government",
+    "ministry",
+    "department of",
+    "police",
+    "sheriff",
+    "coast guard",
+    "customs",
+    "border protection",
+    "nasa",
+    "federal aviation",
+];
+
+/// Whether an aircraft is civil, government or military.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AircraftClass {
+    Civil,
+    Government,
+    Military,
+}
+
+impl AircraftClass {
+    /// Classifies `flight`; military wins over government when hints
+    /// disagree.
+    pub fn of(flight: &Flight) -> Self {
+        let callsign = flight.callsign.trim().to_uppercase();
+        let operator = format!(" {} ", words(flight.operator.as_deref().unwrap_or("")));
+
+        if (!flight.non_icao && icao::lookup(&flight.icao24).is_some_and(|block| block.military))
+            || MILITARY_CALLSIGNS
+                .iter()
+                .any(|p| has_callsign_prefix(&callsign, p))
+            || MILITARY_OPERATORS
+                .iter()
+                .any(|p| has_operator_words(&operator, p))
+        {
+            AircraftClass::Military
+        } else if GOVERNMENT_CALLSIGNS
+            .iter()
+            .any(|p| has_callsign_prefix(&callsign, p))
+            || GOVERNMENT_OPERATORS
+                .iter()
+                .any(|p| has_operator_words(&operator, p))
+        {
+            AircraftClass::Government
+        } else {
+            AircraftClass::Civil
+        }
+    }
+
+    /// Short tag for lists; empty for civil aircraft.
+    pub fn tag(self) -> &'static str {
+        match self {
+            AircraftClass::Civil => "",
+            AircraftClass::Government => "GOV",
+            AircraftClass::Military => "MIL",
+        }
+    }
+
+    /// Whether no military or government hint matched.
+    pub fn is_civil(self) -> bool {
+        self == AircraftClass::Civil
+    }
+}
+
+/// Whether `callsign` is `prefix` followed by a flight number, so
+/// `RCH123` matches `RCH` but the airline callsign `RCHX12` does not.
+fn has_callsign_prefix(callsign: &str, prefix: &str) -> bool {
+    callsign
+        .strip_prefix(prefix)
+        .is_some_and(|rest| !rest.is_empty() && rest.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Lowercase alphanumeric words of `s`, joined by single spaces.
+fn words(s: &str) -> String {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether `fragment` occurs as whole words in `operator` (as padded by
+/// [`words`] with a space either side), so "navy" matches "US Navy" but not
+/// "Navya Aviation".
+fn has_operator_words(operator: &str, fragment: &str) -> bool {
+    operator.contains(&format!(" {} ", words(fragment)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flight(icao24: &str, callsign: &str, operator: Option<&str>) -> Flight {
+        Flight {
+            icao24: icao24.to_string(),
+            callsign: callsign.to_string(),
+            operator: operator.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        use AircraftClass::*;
+        assert_eq!(
+            AircraftClass::of(&flight("a1b2c3", "UAL123", Some("United Airlines"))),
+            Civil
+        );
+        // Address block alone.
+        assert_eq!(AircraftClass::of(&flight("ae1234", "", None)), Military);
+        // Callsign alone.
+        assert_eq!(
+            AircraftClass::of(&flight("a1b2c3", "RCH871 ", None)),
+            Military
+        );
+        assert_eq!(
+            AircraftClass::of(&flight("a1b2c3", "NASA504", None)),
+            Government
+        );
+        // Operator alone.
+        assert_eq!(
+            AircraftClass::of(&flight(
+                "a1b2c3",
+                "N123AB",
+                Some("Los Angeles County Sheriff")
+            )),
+            Government
+        );
+        assert_eq!(
+            AircraftClass::of(&flight("3c1234", "", Some("German Air Force"))),
+            Military
+        );
+        assert_eq!(
+            AircraftClass::of(&flight("a1b2c3", "", Some("U.S. Navy/VR-58"))),
+            Military
+        );
+        // Fragments only match whole words.
+        for civil in ["Navya Aviation", "Armytage Charters", "Policelli Air"] {
+            assert_eq!(
+                AircraftClass::of(&flight("a1b2c3", "", Some(civil))),
+                Civil,
+                "{}",
+                civil
+            );
+        }
+    }
+
+    #[test]
+    fn test_callsign_prefix_needs_flight_number() {
+        assert!(has_callsign_prefix("CG2004", "CG"));
+        assert!(!has_callsign_prefix("CGX123", "CG"));
+        assert!(!has_callsign_prefix("SAM", "SAM"));
+        assert!(!has_callsign_prefix("POLAR1", "POL"));
+    }
+}
@@ -4,6 +4,8 @@
 //! If the file is missing or invalid, defaults are used and a default file is
 //! written so the user can edit it. See [`Config::load`].
 
+use crate::phase::PhaseFilter;
+use crate::units::{AltitudeUnit, DistanceUnit, SpeedUnit, UnitSystem, VerticalRateUnit};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use tracing::{info, warn};
@@ -20,6 +22,9 @@ pub struct Config {
     pub api: ApiConfig,
     /// UI defaults.
     pub ui: UiConfig,
+    /// Track history retention.
+    #[serde(default)]
+    pub tracks: TrackConfig,
 }
 
 /// Location source and search radius for the OpenSky API.
@@ -34,13 +39,90 @@ pub struct LocationConfig {
     pub manual_lon: f64,
     /// Search radius in kilometres for the OpenSky bounding-box query.
     pub detection_radius: f64,
+    /// Observer height above the WGS-84 ellipsoid in meters (roughly height
+    /// above sea level), used for elevation and slant range and as the field
+    /// elevation for flight phases.
+    #[serde(default)]
+    pub observer_altitude_m: f64,
 }
 
 /// API-related settings.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ApiConfig {
-    /// Seconds between OpenSky API fetches.
+    /// Seconds between flight source fetches.
     pub poll_interval_seconds: u64,
+    /// Which flight source to poll. Defaults to OpenSky when omitted.
+    #[serde(default)]
+    pub source: SourceConfig,
+    /// Further sources polled alongside `source`; their flights are merged
+    /// by ICAO24 (`[[api.extra_sources]]` tables).
+    #[serde(default)]
+    pub extra_sources: Vec<SourceConfig>,
+}
+
+/// Flight source selection; maps to the `[api.source]` table, keyed by `kind`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum SourceConfig {
+    /// OpenSky Network REST API. Anonymous unless both `client_id` and
+    /// `client_secret` are set, in which case requests are authenticated via
+    /// OAuth2 client credentials and draw on the account's larger credit pool.
+    OpenSky {
+        /// API client ID from the OpenSky account page.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client_id: Option<String>,
+        /// API client secret paired with `client_id`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client_secret: Option<String>,
+    },
+    /// `aircraft.json` from a local dump1090-fa, readsb or tar1090 receiver.
+    Dump1090 {
+        /// `http(s)://` URL or local file path of `aircraft.json`.
+        url: String,
+    },
+    /// SBS-1 / BaseStation CSV stream (usually port 30003).
+    Sbs {
+        /// `host:port` of the feed.
+        address: String,
+        /// Milliseconds between flight updates pushed to the UI.
+        #[serde(default = "default_stream_update_ms")]
+        update_interval_ms: u64,
+    },
+    /// Beast binary Mode-S stream (usually port 30005), decoded natively.
+    Beast {
+        /// `host:port` of the feed.
+        address: String,
+        /// Milliseconds between flight updates pushed to the UI.
+        #[serde(default = "default_stream_update_ms")]
+        update_interval_ms: u64,
+    },
+    /// AVR raw hex frames from a TCP feed (usually port 30002), a capture
+    /// file or stdin.
+    Avr {
+        /// `host:port` of the feed. Defaults to `localhost:30002` when
+        /// neither `address` nor `path` is set.
+        #[serde(default)]
+        address: Option<String>,
+        /// Capture file to replay instead of a live feed; `-` reads stdin.
+        #[serde(default)]
+        path: Option<String>,
+        /// Milliseconds between flight updates pushed to the UI.
+        #[serde(default = "default_stream_update_ms")]
+        update_interval_ms: u64,
+    },
+}
+
+impl Default for SourceConfig {
+    fn default() -> Self {
+        SourceConfig::OpenSky {
+            client_id: None,
+            client_secret: None,
+        }
+    }
+}
+
+fn default_stream_update_ms() -> u64 {
+    1000
 }
 
 /// UI-related settings.
@@ -48,6 +130,71 @@ pub struct ApiConfig {
 pub struct UiConfig {
     /// Initial view: `"Dashboard"` or `"Spotter"`. Any other value falls back to Spotter.
     pub default_view: String,
+    /// Hide aircraft lower in the sky than `min_elevation_deg`.
+    #[serde(default)]
+    pub horizon_filter: bool,
+    /// Elevation threshold in degrees for `horizon_filter`; 0 is the horizon.
+    #[serde(default)]
+    pub min_elevation_deg: f64,
+    /// Aircraft predicted to pass within this many kilometres are flagged
+    /// as passing overhead.
+    #[serde(default = "default_overhead_radius_km")]
+    pub overhead_radius_km: f64,
+    /// How far ahead, in minutes, overhead passes are predicted.
+    #[serde(default = "default_overhead_window_minutes")]
+    pub overhead_window_minutes: u64,
+    /// Show only military and government aircraft.
+    #[serde(default)]
+    pub military_only: bool,
+    /// Raise an alert when a military or government aircraft appears.
+    #[serde(default)]
+    pub military_alert: bool,
+    /// Flight phases shown in the flight list: `"all"`, `"departures"`,
+    /// `"arrivals"`, `"terminal"` (both), `"ground"` or `"enroute"`.
+    #[serde(default)]
+    pub phase_filter: PhaseFilter,
+    /// Display unit preset: `"metric"`, `"imperial"` or `"aviation"`.
+    #[serde(default)]
+    pub units: UnitSystem,
+    /// Overrides the preset's altitude unit (`"m"` or `"ft"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub altitude_unit: Option<AltitudeUnit>,
+    /// Overrides the preset's speed unit (`"km/h"`, `"mph"`, `"kt"`, `"m/s"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_unit: Option<SpeedUnit>,
+    /// Overrides the preset's distance unit (`"km"`, `"mi"`, `"nm"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance_unit: Option<DistanceUnit>,
+    /// Overrides the preset's vertical rate unit (`"m/s"` or `"ft/min"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vertical_rate_unit: Option<VerticalRateUnit>,
+}
+
+fn default_overhead_radius_km() -> f64 {
+    2.0
+}
+
+fn default_overhead_window_minutes() -> u64 {
+    15
+}
+
+/// How much per-aircraft track history to keep; maps to `[tracks]`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct TrackConfig {
+    /// Points kept per aircraft before the oldest is dropped.
+    pub max_points: usize,
+    /// Seconds without a report after which an aircraft's track is dropped.
+    pub expire_after_seconds: u64,
+}
+
+impl Default for TrackConfig {
+    fn default() -> Self {
+        Self {
+            max_points: 240,
+            expire_after_seconds: 300,
+        }
+    }
 }
 
 impl Default for LocationConfig {
@@ -57,6 +204,7 @@ impl Default for LocationConfig {
             manual_lat: 37.7749,
             manual_lon: -122.4194,
             detection_radius: 50.0,
+            observer_altitude_m: 0.0,
         }
     }
 }
@@ -65,6 +213,8 @@ impl Default for ApiConfig {
     fn default() -> Self {
         Self {
             poll_interval_seconds: 30,
+            source: SourceConfig::default(),
+            extra_sources: Vec::new(),
         }
     }
 }
@@ -73,6 +223,18 @@ impl Default for UiConfig {
     fn default() -> Self {
         Self {
             default_view: "Dashboard".to_string(),
+            horizon_filter: false,
+            min_elevation_deg: 0.0,
+            overhead_radius_km: default_overhead_radius_km(),
+            overhead_window_minutes: default_overhead_window_minutes(),
+            military_only: false,
+            military_alert: false,
+            phase_filter: PhaseFilter::default(),
+            units: UnitSystem::default(),
+            altitude_unit: None,
+            speed_unit: None,
+            distance_unit: None,
+            vertical_rate_unit: None,
         }
     }
 }
@@ -0,0 +1,328 @@
+//! Crash-safe aircraft DB builds.
+//!
+//! [`build_database`] writes the whole DB to `<db>.tmp`, records a `metadata`
+//! table describing what it built from, and only then renames the file over
+//! the real one. A build that fails or is killed halfway leaves the previous
+//! DB (or none) in place, never a partial one. [`check_database`] reads the
+//! metadata back at startup so a DB built by an older version, or one that
+//! does not match its own row count, can be offered for a rebuild.
+
+use super::formats::{self, SourceFormat};
+use super::migrations::{self, SCHEMA_VERSION};
+use color_eyre::eyre::{Result, WrapErr};
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Aircraft CSV the DB is built from when `data/` has no newer one,
+/// relative to the working directory.
+pub const CSV_PATH: &str = "data/aircraft-database-complete-2025-08.csv";
+/// Columns of the `aircraft` table, named as in the OpenSky CSV header.
+/// `icao24` comes first and is required; the others are imported when the
+/// CSV has them.
+pub const AIRCRAFT_COLUMNS: &[&str] = &[
+    "icao24",
+    "manufacturerName",
+    "model",
+    "operator",
+    "operatorCallsign",
+    "owner",
+    "registration",
+    "typecode",
+    "manufacturerIcao",
+    "serialNumber",
+    "built",
+    "engines",
+    "categoryDescription",
+    "icaoAircraftClass",
+    "operatorIcao",
+    "operatorIata",
+    "country",
+    "registered",
+    "regUntil",
+    "status",
+];
+
+/// What a DB was built from, as stored in its `metadata` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbMetadata {
+    pub schema_version: u32,
+    /// Hex SHA-256 of the file last built or imported from.
+    pub csv_sha256: String,
+    /// Rows in the `aircraft` table when the build finished.
+    pub row_count: u64,
+    /// Unix time (seconds) the build finished.
+    pub built_at: i64,
+    /// Unix time (seconds) of the last import into the built DB, if any.
+    pub imported_at: Option<i64>,
+    /// Path of the file last built or imported from.
+    pub source: String,
+}
+
+/// State of the DB file found at startup.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DbStatus {
+    /// No DB yet; build one.
+    #[default]
+    Missing,
+    /// Complete and consistent with its metadata.
+    Ready(DbMetadata),
+    /// Usable at best; the reason is shown when offering a rebuild.
+    Invalid(String),
+}
+
+/// Path the DB is built at before being renamed to `db_path`.
+pub fn temp_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path.as_os_str().to_owned();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+/// Builds the DB at `db_path` from the registry file at `source`, read as
+/// `format` (see [`formats`]), replacing any existing DB only once the new
+/// one is complete.
+///
+/// `progress` is called every few thousand rows with the fraction of the
+/// file read so far (0.0–1.0). Malformed records are skipped and counted in
+/// the log.
+///
+/// # Errors
+///
+/// Fails if the file cannot be read or lacks an ICAO24 column, or on any
+/// SQLite or filesystem error. The temporary file is removed and the
+/// existing DB left untouched.
+pub fn build_database(
+    source: &Path,
+    format: &SourceFormat,
+    db_path: &Path,
+    progress: impl FnMut(f32),
+) -> Result<DbMetadata> {
+    let tmp = temp_path(db_path);
+    // Leftover from a build that was killed.
+    let _ = std::fs::remove_file(&tmp);
+
+    let result = write_database(source, format, &tmp, progress).and_then(|metadata| {
+        std::fs::rename(&tmp, db_path)
+            .wrap_err_with(|| format!("Cannot move {} into place", tmp.display()))?;
+        Ok(metadata)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+fn write_database(
+    source: &Path,
+    format: &SourceFormat,
+    db_path: &Path,
+    mut progress: impl FnMut(f32),
+) -> Result<DbMetadata> {
+    let mut conn = Connection::open(db_path)
+        .wrap_err_with(|| format!("Cannot create {}", db_path.display()))?;
+    migrations::migrate(&mut conn)?;
+
+    let db_tx = conn.unchecked_transaction()?;
+    let loaded = formats::load(&db_tx, "aircraft", source, format, &mut progress)?;
+    let row_count: u64 = db_tx.query_row("SELECT COUNT(*) FROM aircraft", [], |r| r.get(0))?;
+    let metadata = DbMetadata {
+        schema_version: SCHEMA_VERSION,
+        csv_sha256: loaded.sha256,
+        row_count,
+        built_at: chrono::Utc::now().timestamp(),
+        imported_at: None,
+        source: source.display().to_string(),
+    };
+    write_metadata(&db_tx, &metadata)?;
+    db_tx.commit()?;
+    conn.close().map_err(|(_, e)| e)?;
+
+    info!(
+        "Built aircraft DB with {} rows from {}",
+        metadata.row_count, metadata.source
+    );
+    progress(1.0);
+    Ok(metadata)
+}
+
+pub(super) fn write_metadata(conn: &Connection, metadata: &DbMetadata) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare("INSERT OR REPLACE INTO metadata (key, value) VALUES (?, ?)")?;
+    stmt.execute(["schema_version", &metadata.schema_version.to_string()])?;
+    stmt.execute(["csv_sha256", &metadata.csv_sha256])?;
+    stmt.execute(["row_count", &metadata.row_count.to_string()])?;
+    stmt.execute(["built_at", &metadata.built_at.to_string()])?;
+    if let Some(imported_at) = metadata.imported_at {
+        stmt.execute(["imported_at", &imported_at.to_string()])?;
+    }
+    stmt.execute(["source", &metadata.source])?;
+    Ok(())
+}
+
+/// Reads the metadata of the DB at `db_path` and checks it against the
+/// `aircraft` table and the schema version. Run
+/// [`migrate_database`](super::migrate_database) first to upgrade DBs built
+/// for an older schema.
+pub fn check_database(db_path: &Path) -> DbStatus {
+    if !db_path.exists() {
+        return DbStatus::Missing;
+    }
+    let conn = match Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY) {
+        Ok(conn) => conn,
+        Err(e) => return DbStatus::Invalid(format!("cannot open: {}", e)),
+    };
+    match read_metadata(&conn) {
+        Ok(Some(metadata)) => verify(&conn, metadata),
+        Ok(None) => {
+            DbStatus::Invalid("no build metadata; the last build may not have finished".to_string())
+        }
+        Err(e) => DbStatus::Invalid(format!("unreadable metadata: {}", e)),
+    }
+}
+
+fn verify(conn: &Connection, metadata: DbMetadata) -> DbStatus {
+    match migrations::schema_version(conn) {
+        Ok(version) if version == SCHEMA_VERSION => {}
+        Ok(version) => {
+            return DbStatus::Invalid(format!(
+                "schema v{} (expected v{})",
+                version, SCHEMA_VERSION
+            ))
+        }
+        Err(e) => return DbStatus::Invalid(format!("cannot read schema version: {}", e)),
+    }
+    if metadata.schema_version != SCHEMA_VERSION {
+        return DbStatus::Invalid(format!(
+            "schema version {} (expected {})",
+            metadata.schema_version, SCHEMA_VERSION
+        ));
+    }
+    match conn.query_row("SELECT COUNT(*) FROM aircraft", [], |r| r.get::<_, u64>(0)) {
+        Ok(rows) if rows == metadata.row_count => DbStatus::Ready(metadata),
+        Ok(rows) => DbStatus::Invalid(format!(
+            "{} aircraft rows, metadata says {}",
+            rows, metadata.row_count
+        )),
+        Err(e) => DbStatus::Invalid(format!("cannot count aircraft: {}", e)),
+    }
+}
+
+/// The metadata table, or `None` if it does not exist or is incomplete.
+fn read_metadata(conn: &Connection) -> rusqlite::Result<Option<DbMetadata>> {
+    let has_table = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'metadata'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !has_table {
+        return Ok(None);
+    }
+
+    let get = |key: &str| -> rusqlite::Result<Option<String>> {
+        conn.query_row("SELECT value FROM metadata WHERE key = ?", [key], |r| {
+            r.get(0)
+        })
+        .optional()
+    };
+    let (Some(version), Some(hash), Some(rows), Some(built_at)) = (
+        get("schema_version")?,
+        get("csv_sha256")?,
+        get("row_count")?,
+        get("built_at")?,
+    ) else {
+        return Ok(None);
+    };
+    let (Ok(schema_version), Ok(row_count), Ok(built_at)) =
+        (version.parse(), rows.parse(), built_at.parse())
+    else {
+        return Ok(None);
+    };
+    Ok(Some(DbMetadata {
+        schema_version,
+        csv_sha256: hash,
+        row_count,
+        built_at,
+        imported_at: get("imported_at")?.and_then(|t| t.parse().ok()),
+        source: get("source")?.unwrap_or_default(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::fixtures;
+    use sha2::{Digest, Sha256};
+    use tempfile::TempDir;
+
+    const CSV: &str = "'icao24','manufacturerName','model','registration','typecode','built'\n\
+        'ABC123','Boeing','737-800','N123AB','B738','2001-05-01'\n\
+        'def456','Airbus','A320','D-AIZZ','A320',''\n\
+        '','Nobody','','','',''\n";
+
+    /// Writes `csv` to a fresh temp dir and returns it with (csv, db) paths
+    /// in it.
+    fn fixture(csv: &str) -> (TempDir, PathBuf, PathBuf) {
+        let dir = fixtures::temp_dir();
+        let csv_path = dir.path().join("aircraft.csv");
+        std::fs::write(&csv_path, csv).unwrap();
+        let db = dir.path().join("aircraft.db");
+        (dir, csv_path, db)
+    }
+
+    #[test]
+    fn test_build_records_metadata_and_checks_out() {
+        let (_dir, csv, db) = fixture(CSV);
+        assert_eq!(check_database(&db), DbStatus::Missing);
+
+        let mut last = 0.0;
+        let metadata = build_database(&csv, &SourceFormat::OpenSky, &db, |p| last = p).unwrap();
+        assert_eq!(last, 1.0);
+        assert_eq!(metadata.row_count, 2);
+        assert_eq!(metadata.schema_version, SCHEMA_VERSION);
+        assert_eq!(
+            metadata.csv_sha256,
+            format!("{:x}", Sha256::digest(CSV.as_bytes()))
+        );
+        assert!(!temp_path(&db).exists());
+        assert_eq!(check_database(&db), DbStatus::Ready(metadata));
+
+        // Columns added by migrations are imported too.
+        let built: String = Connection::open(&db)
+            .unwrap()
+            .query_row(
+                "SELECT built FROM aircraft WHERE icao24 = 'abc123'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(built, "2001-05-01");
+
+        // Rows lost after the build no longer match the metadata.
+        Connection::open(&db)
+            .unwrap()
+            .execute("DELETE FROM aircraft WHERE icao24 = 'abc123'", [])
+            .unwrap();
+        assert!(matches!(check_database(&db), DbStatus::Invalid(_)));
+    }
+
+    #[test]
+    fn test_failed_build_keeps_previous_db() {
+        let (_dir, csv, db) = fixture(CSV);
+        let built = build_database(&csv, &SourceFormat::OpenSky, &db, |_| {}).unwrap();
+
+        std::fs::write(&csv, "'registration'\n'N1'\n").unwrap();
+        assert!(build_database(&csv, &SourceFormat::OpenSky, &db, |_| {}).is_err());
+        assert!(!temp_path(&db).exists());
+        assert_eq!(check_database(&db), DbStatus::Ready(built));
+
+        // A DB from before metadata existed, or an interrupted one, is
+        // flagged rather than trusted.
+        Connection::open(&db)
+            .unwrap()
+            .execute("DROP TABLE metadata", [])
+            .unwrap();
+        assert!(matches!(check_database(&db), DbStatus::Invalid(_)));
+    }
+}
@@ -0,0 +1,731 @@
+//! Aircraft registry file formats for DB builds and imports.
+//!
+//! Files may be plain or compressed (`.gz`, `.zst`, or the first CSV/text
+//! file in a `.zip`), chosen by extension. Three layouts are understood
+//! ([`SourceFormat`]):
+//!
+//! - the OpenSky aircraft database CSV, with single-quoted fields named
+//!   like the `aircraft` columns;
+//! - the FAA releasable aircraft registry: `MASTER.txt` joined with
+//!   `ACFTREF.txt` for manufacturer and model, either both in the FAA's
+//!   `ReleasableAircraft.zip` or side by side in a directory;
+//! - any other delimited file, described by a [`ColumnMapping`] TOML.
+
+use super::{ImportMode, AIRCRAFT_COLUMNS};
+use color_eyre::eyre::{bail, eyre, Result, WrapErr};
+use rusqlite::Connection;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use tracing::warn;
+
+/// Rows between progress callbacks.
+const PROGRESS_EVERY: usize = 2000;
+
+/// Maps `aircraft` columns to the headers of a delimited registry file.
+///
+/// ```toml
+/// delimiter = ";"
+/// quote = "\""
+///
+/// [columns]
+/// icao24 = "ModeS"
+/// registration = "Registration"
+/// typecode = "ICAO Type"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ColumnMapping {
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    #[serde(default = "default_quote")]
+    pub quote: char,
+    /// `aircraft` column (see [`AIRCRAFT_COLUMNS`]) to file header. Headers
+    /// match case-insensitively; `icao24` is required.
+    pub columns: BTreeMap<String, String>,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_quote() -> char {
+    '"'
+}
+
+impl ColumnMapping {
+    /// The OpenSky CSV: single-quoted, headers named like the columns.
+    pub fn opensky() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '\'',
+            columns: AIRCRAFT_COLUMNS
+                .iter()
+                .map(|c| (c.to_string(), c.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Reads and validates a mapping TOML.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be read or parsed, names a column the
+    /// `aircraft` table does not have, lacks `icao24`, or uses a non-ASCII
+    /// delimiter or quote.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Cannot read mapping {}", path.display()))?;
+        let mapping: Self = toml::from_str(&text)
+            .wrap_err_with(|| format!("Invalid mapping {}", path.display()))?;
+        mapping.validate()?;
+        Ok(mapping)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(unknown) = self
+            .columns
+            .keys()
+            .find(|c| !AIRCRAFT_COLUMNS.contains(&c.as_str()))
+        {
+            bail!(
+                "Unknown aircraft column '{}'; expected one of {}",
+                unknown,
+                AIRCRAFT_COLUMNS.join(", ")
+            );
+        }
+        if !self.columns.contains_key("icao24") {
+            bail!("Mapping has no 'icao24' column");
+        }
+        if !self.delimiter.is_ascii() || !self.quote.is_ascii() {
+            bail!("Delimiter and quote must be ASCII characters");
+        }
+        Ok(())
+    }
+}
+
+/// Layout of a registry file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceFormat {
+    /// OpenSky aircraft database CSV.
+    OpenSky,
+    /// FAA releasable aircraft registry (`MASTER.txt` + `ACFTREF.txt`).
+    Faa,
+    /// Any delimited file with a header row.
+    Mapped(ColumnMapping),
+}
+
+impl SourceFormat {
+    /// Guesses the format from the file name: `MASTER*` files and
+    /// `ReleasableAircraft*.zip` are FAA, anything else OpenSky.
+    pub fn detect(path: &Path) -> Self {
+        let name = file_name(path);
+        if name.starts_with("master") || name.starts_with("releasableaircraft") {
+            SourceFormat::Faa
+        } else {
+            SourceFormat::OpenSky
+        }
+    }
+
+    /// How a file of this format is imported unless told otherwise. Only
+    /// the OpenSky dump lists every aircraft; the FAA registry covers US
+    /// tails and a mapped file whatever it happens to hold, so a full import
+    /// of either would delete every other aircraft from the DB.
+    pub fn default_import_mode(&self) -> ImportMode {
+        match self {
+            SourceFormat::OpenSky => ImportMode::Full,
+            SourceFormat::Faa | SourceFormat::Mapped(_) => ImportMode::Delta,
+        }
+    }
+
+    /// Short name for messages.
+    pub fn label(&self) -> &'static str {
+        match self {
+            SourceFormat::OpenSky => "OpenSky CSV",
+            SourceFormat::Faa => "FAA registry",
+            SourceFormat::Mapped(_) => "mapped CSV",
+        }
+    }
+}
+
+/// What [`load`] wrote.
+pub(super) struct Loaded {
+    /// Columns of [`AIRCRAFT_COLUMNS`] the file supplied, `icao24` first.
+    pub columns: Vec<&'static str>,
+    /// Hex SHA-256 of the file as given (compressed, if it is).
+    pub sha256: String,
+}
+
+/// Inserts every aircraft in the file at `path` into `table`, which has
+/// the `aircraft` columns, replacing rows with the same ICAO24. Only the
+/// columns the file supplies are written. Malformed records and records
+/// without an ICAO24 are skipped and counted in the log.
+pub(super) fn load(
+    conn: &Connection,
+    table: &str,
+    path: &Path,
+    format: &SourceFormat,
+    progress: &mut impl FnMut(f32),
+) -> Result<Loaded> {
+    let sha256 = hash_file(path)?;
+    let columns = match format {
+        SourceFormat::OpenSky => {
+            load_mapped(conn, table, path, &ColumnMapping::opensky(), progress)?
+        }
+        SourceFormat::Mapped(mapping) => load_mapped(conn, table, path, mapping, progress)?,
+        SourceFormat::Faa => load_faa(conn, table, path, progress)?,
+    };
+    Ok(Loaded { columns, sha256 })
+}
+
+fn load_mapped(
+    conn: &Connection,
+    table: &str,
+    path: &Path,
+    mapping: &ColumnMapping,
+    progress: &mut impl FnMut(f32),
+) -> Result<Vec<&'static str>> {
+    let wanted: Vec<&'static str> = AIRCRAFT_COLUMNS
+        .iter()
+        .copied()
+        .filter(|c| mapping.columns.contains_key(*c))
+        .collect();
+    let headers: Vec<&str> = wanted
+        .iter()
+        .map(|c| mapping.columns[*c].as_str())
+        .collect();
+
+    with_input(path, is_table_entry, |input| {
+        let mut rows = Table::new(
+            input,
+            mapping.delimiter as u8,
+            mapping.quote as u8,
+            &headers,
+        )?;
+        if !rows.has(0) {
+            bail!(
+                "CSV Error: Could not find '{}' column for icao24. Found: {:?}",
+                headers[0],
+                rows.found
+            );
+        }
+        let present: Vec<usize> = (0..wanted.len()).filter(|&i| rows.has(i)).collect();
+        let columns: Vec<&'static str> = present.iter().map(|&i| wanted[i]).collect();
+
+        let mut insert = Inserter::new(conn, table, &columns)?;
+        let mut values = Vec::new();
+        let mut row = Vec::with_capacity(present.len());
+        while rows.next(&mut values)? {
+            row.clear();
+            row.extend(present.iter().map(|&i| std::mem::take(&mut values[i])));
+            insert.row(&mut row, || rows.progress(), progress)?;
+        }
+        insert.finish(path, rows.skipped);
+        Ok(columns)
+    })
+}
+
+/// `MASTER.txt` headers read, in order.
+const FAA_MASTER: &[&str] = &[
+    "MODE S CODE HEX",
+    "N-NUMBER",
+    "SERIAL NUMBER",
+    "YEAR MFR",
+    "NAME",
+    "CERT ISSUE DATE",
+    "EXPIRATION DATE",
+    "STATUS CODE",
+    "MFR MDL CODE",
+];
+/// Columns filled from `MASTER.txt`, matching [`FAA_MASTER`] up to the
+/// aircraft reference code.
+const FAA_COLUMNS: &[&str] = &[
+    "icao24",
+    "registration",
+    "serialNumber",
+    "built",
+    "owner",
+    "registered",
+    "regUntil",
+    "status",
+];
+/// Columns filled from `ACFTREF.txt`, when it is found.
+const FAA_REF_COLUMNS: &[&str] = &["manufacturerName", "model", "engines"];
+
+fn load_faa(
+    conn: &Connection,
+    table: &str,
+    path: &Path,
+    progress: &mut impl FnMut(f32),
+) -> Result<Vec<&'static str>> {
+    let reference = read_faa_reference(path)?;
+    if reference.is_none() {
+        warn!(
+            "No ACFTREF.txt next to {}; importing without manufacturer and model",
+            path.display()
+        );
+    }
+
+    let mut columns: Vec<&'static str> = FAA_COLUMNS.to_vec();
+    if reference.is_some() {
+        columns.extend_from_slice(FAA_REF_COLUMNS);
+    }
+    columns.push("country");
+
+    with_input(
+        path,
+        |name| is_named(name, "master"),
+        |input| {
+            let mut rows = Table::new(input, b',', b'"', FAA_MASTER)?;
+            if !rows.has(0) {
+                bail!("{} is not an FAA MASTER file", path.display());
+            }
+            let mut insert = Inserter::new(conn, table, &columns)?;
+            let mut values = Vec::new();
+            let mut row = Vec::with_capacity(columns.len());
+            while rows.next(&mut values)? {
+                row.clear();
+                row.extend(values[..FAA_COLUMNS.len()].iter_mut().map(std::mem::take));
+                // The file omits the N prefix of US registrations.
+                if !row[1].is_empty() {
+                    row[1].insert(0, 'N');
+                }
+                if let Some(reference) = &reference {
+                    let code = &values[FAA_COLUMNS.len()];
+                    let [mfr, model, engines] = reference.get(code).cloned().unwrap_or_default();
+                    row.extend([mfr, model, engines]);
+                }
+                row.push("United States".to_string());
+                insert.row(&mut row, || rows.progress(), progress)?;
+            }
+            insert.finish(path, rows.skipped);
+            Ok(columns)
+        },
+    )
+}
+
+/// `ACFTREF.txt` as code → [manufacturer, model, engine count], from the
+/// same archive as `master` or the same directory. `None` if not found.
+fn read_faa_reference(master: &Path) -> Result<Option<HashMap<String, [String; 3]>>> {
+    let source = if compression(master) == Compression::Zip {
+        Some(master.to_path_buf())
+    } else {
+        let dir = master.parent().filter(|d| !d.as_os_str().is_empty());
+        std::fs::read_dir(dir.unwrap_or(Path::new(".")))
+            .ok()
+            .and_then(|entries| {
+                entries
+                    .filter_map(|e| e.ok().map(|e| e.path()))
+                    .find(|p| file_name(p).starts_with("acftref"))
+            })
+    };
+    let Some(source) = source else {
+        return Ok(None);
+    };
+
+    let found = with_input(
+        &source,
+        |name| is_named(name, "acftref"),
+        |input| {
+            let mut rows = Table::new(input, b',', b'"', &["CODE", "MFR", "MODEL", "NO-ENG"])?;
+            let mut reference = HashMap::new();
+            let mut values = Vec::new();
+            while rows.next(&mut values)? {
+                let [code, mfr, model, engines] = std::mem::take(&mut values)
+                    .try_into()
+                    .map_err(|_| eyre!("ACFTREF row has the wrong width"))?;
+                reference.insert(code, [mfr, model, engines]);
+            }
+            Ok(reference)
+        },
+    );
+    match found {
+        Ok(reference) => Ok(Some(reference)),
+        // A ReleasableAircraft.zip without ACFTREF.txt.
+        Err(e) if e.downcast_ref::<MissingEntry>().is_some() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Lowercase file name of `path`.
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Whether an archive entry's file name starts with `stem`, ignoring case.
+fn is_named(entry: &str, stem: &str) -> bool {
+    let name = entry.rsplit('/').next().unwrap_or(entry).to_lowercase();
+    name.starts_with(stem)
+}
+
+/// Whether an archive entry looks like a delimited table.
+fn is_table_entry(entry: &str) -> bool {
+    let name = entry.to_lowercase();
+    name.ends_with(".csv") || name.ends_with(".txt")
+}
+
+/// Hex SHA-256 of a file.
+fn hash_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path).wrap_err_with(|| format!("Missing file {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    Plain,
+    Gzip,
+    Zstd,
+    Zip,
+}
+
+/// Compression of a file, from its extension.
+fn compression(path: &Path) -> Compression {
+    let name = file_name(path);
+    if name.ends_with(".gz") {
+        Compression::Gzip
+    } else if name.ends_with(".zst") || name.ends_with(".zstd") {
+        Compression::Zstd
+    } else if name.ends_with(".zip") {
+        Compression::Zip
+    } else {
+        Compression::Plain
+    }
+}
+
+/// A zip archive has no entry [`with_input`] was asked for.
+#[derive(Debug)]
+struct MissingEntry(PathBuf);
+
+impl std::fmt::Display for MissingEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no matching file in {}", self.0.display())
+    }
+}
+
+impl std::error::Error for MissingEntry {}
+
+/// A decompressed file plus how far through it reading is.
+struct Input<'a> {
+    reader: Box<dyn Read + 'a>,
+    /// Bytes consumed of `total`: compressed bytes for `.gz` and `.zst`,
+    /// entry bytes for `.zip`.
+    read: Rc<Cell<u64>>,
+    total: u64,
+}
+
+/// Opens `path`, decompressing by extension, and passes it to `f`. In a
+/// zip, reads the first entry whose name satisfies `entry`.
+fn with_input<T>(
+    path: &Path,
+    entry: impl Fn(&str) -> bool,
+    f: impl FnOnce(Input<'_>) -> Result<T>,
+) -> Result<T> {
+    let file = File::open(path).wrap_err_with(|| format!("Missing file {}", path.display()))?;
+    let read = Rc::new(Cell::new(0));
+    let kind = compression(path);
+    if kind == Compression::Zip {
+        let mut archive = zip::ZipArchive::new(BufReader::new(file))
+            .wrap_err_with(|| format!("Cannot open zip {}", path.display()))?;
+        let index = (0..archive.len())
+            .find(|&i| archive.by_index(i).is_ok_and(|e| entry(e.name())))
+            .ok_or_else(|| MissingEntry(path.to_path_buf()))?;
+        let file = archive.by_index(index)?;
+        let total = file.size();
+        return f(Input {
+            reader: Box::new(Counting {
+                inner: file,
+                read: read.clone(),
+            }),
+            read,
+            total,
+        });
+    }
+
+    let total = file.metadata()?.len();
+    let counted = Counting {
+        inner: file,
+        read: read.clone(),
+    };
+    let reader: Box<dyn Read> = match kind {
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(BufReader::new(counted))),
+        Compression::Zstd => Box::new(zstd::Decoder::new(counted)?),
+        _ => Box::new(BufReader::new(counted)),
+    };
+    f(Input {
+        reader,
+        read,
+        total,
+    })
+}
+
+/// Counts the bytes read through it.
+struct Counting<R> {
+    inner: R,
+    read: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for Counting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read.set(self.read.get() + n as u64);
+        Ok(n)
+    }
+}
+
+/// Delimited text with a header row, read by header name.
+struct Table<'a> {
+    rdr: csv::Reader<Box<dyn Read + 'a>>,
+    /// Column of each requested header, `None` if the file lacks it.
+    indices: Vec<Option<usize>>,
+    /// The file's headers, for error messages.
+    found: Vec<String>,
+    record: csv::StringRecord,
+    read: Rc<Cell<u64>>,
+    total: f32,
+    skipped: usize,
+}
+
+impl<'a> Table<'a> {
+    fn new(input: Input<'a>, delimiter: u8, quote: u8, headers: &[&str]) -> Result<Self> {
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .quote(quote)
+            .flexible(true)
+            .has_headers(true)
+            .from_reader(input.reader);
+        let found: Vec<String> = rdr
+            .headers()
+            .wrap_err("Header Error")?
+            .iter()
+            .map(|h| h.trim_start_matches('\u{feff}').trim().to_string())
+            .collect();
+        let indices = headers
+            .iter()
+            .map(|wanted| {
+                found
+                    .iter()
+                    .position(|h| h.eq_ignore_ascii_case(wanted.trim()))
+            })
+            .collect();
+        Ok(Self {
+            rdr,
+            indices,
+            found,
+            record: csv::StringRecord::new(),
+            read: input.read,
+            total: input.total.max(1) as f32,
+            skipped: 0,
+        })
+    }
+
+    /// Whether the file has the `i`th requested header.
+    fn has(&self, i: usize) -> bool {
+        self.indices[i].is_some()
+    }
+
+    /// Reads the next record into `values`, one cleaned value per requested
+    /// header (empty if absent). Skips malformed records; returns `false`
+    /// at the end of the file.
+    fn next(&mut self, values: &mut Vec<String>) -> Result<bool> {
+        loop {
+            match self.rdr.read_record(&mut self.record) {
+                Ok(false) => return Ok(false),
+                Ok(true) => break,
+                Err(e) if e.is_io_error() => return Err(e.into()),
+                Err(_) => self.skipped += 1,
+            }
+        }
+        values.clear();
+        values.extend(self.indices.iter().map(|idx| {
+            idx.and_then(|i| self.record.get(i))
+                .map(|s| s.trim().trim_matches(|c| c == '\'' || c == '"').trim())
+                .unwrap_or("")
+                .to_string()
+        }));
+        Ok(true)
+    }
+
+    /// Fraction of the input read so far.
+    fn progress(&self) -> f32 {
+        (self.read.get() as f32 / self.total).min(1.0)
+    }
+}
+
+/// Writes rows into an `aircraft`-shaped table.
+struct Inserter<'c> {
+    stmt: rusqlite::Statement<'c>,
+    rows: usize,
+    skipped: usize,
+}
+
+impl<'c> Inserter<'c> {
+    fn new(conn: &'c Connection, table: &str, columns: &[&str]) -> Result<Self> {
+        let stmt = conn.prepare(&format!(
+            "INSERT OR REPLACE INTO {} ({}) VALUES ({})",
+            table,
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", ")
+        ))?;
+        Ok(Self {
+            stmt,
+            rows: 0,
+            skipped: 0,
+        })
+    }
+
+    /// Inserts `row` (ICAO24 first, lowercased here) unless it has no
+    /// ICAO24, reporting `fraction()` every [`PROGRESS_EVERY`] rows.
+    fn row(
+        &mut self,
+        row: &mut [String],
+        fraction: impl Fn() -> f32,
+        progress: &mut impl FnMut(f32),
+    ) -> Result<()> {
+        row[0].make_ascii_lowercase();
+        if row[0].is_empty() {
+            self.skipped += 1;
+            return Ok(());
+        }
+        self.stmt.execute(rusqlite::params_from_iter(row.iter()))?;
+        if self.rows % PROGRESS_EVERY == 0 {
+            progress(fraction());
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Logs the records skipped here and the `malformed` ones the reader
+    /// skipped.
+    fn finish(self, path: &Path, malformed: usize) {
+        if self.skipped + malformed > 0 {
+            warn!(
+                "Skipped {} malformed records and {} without an ICAO24 in {}",
+                malformed,
+                self.skipped,
+                path.display()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::fixtures;
+    use std::io::Write;
+
+    /// Loads `path` into an in-memory `aircraft` table and returns
+    /// (icao24, registration, manufacturerName) of every row.
+    fn load_rows(path: &Path, format: &SourceFormat) -> Vec<(String, String, Option<String>)> {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::migrate(&mut conn).unwrap();
+        load(&conn, "aircraft", path, format, &mut |_| {}).unwrap();
+        let mut stmt = conn
+            .prepare("SELECT icao24, registration, manufacturerName FROM aircraft ORDER BY icao24")
+            .unwrap();
+        let rows = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .unwrap();
+        rows.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    const OPENSKY: &str = "'icao24','registration','manufacturerName'\n\
+        'ABC123','N123AB','Boeing'\n";
+
+    #[test]
+    fn test_compressed_inputs() {
+        let tmp = fixtures::temp_dir();
+        let dir = tmp.path();
+        let expected = vec![(
+            "abc123".to_string(),
+            "N123AB".to_string(),
+            Some("Boeing".to_string()),
+        )];
+
+        let gz = dir.join("aircraft-database-complete-2025-09.csv.gz");
+        let mut encoder =
+            flate2::write::GzEncoder::new(File::create(&gz).unwrap(), Default::default());
+        encoder.write_all(OPENSKY.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+        assert_eq!(load_rows(&gz, &SourceFormat::OpenSky), expected);
+
+        let zst = dir.join("aircraft.csv.zst");
+        std::fs::write(&zst, zstd::encode_all(OPENSKY.as_bytes(), 0).unwrap()).unwrap();
+        assert_eq!(load_rows(&zst, &SourceFormat::OpenSky), expected);
+
+        let zip_path = dir.join("aircraft.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        zip.start_file("README", Default::default()).unwrap();
+        zip.write_all(b"not a table").unwrap();
+        zip.start_file("aircraft.csv", Default::default()).unwrap();
+        zip.write_all(OPENSKY.as_bytes()).unwrap();
+        zip.finish().unwrap();
+        assert_eq!(load_rows(&zip_path, &SourceFormat::OpenSky), expected);
+    }
+
+    #[test]
+    fn test_faa_registry_joins_reference() {
+        let tmp = fixtures::temp_dir();
+        let dir = tmp.path();
+        let master = dir.join("MASTER.txt");
+        std::fs::write(
+            &master,
+            "\u{feff}N-NUMBER,SERIAL NUMBER,MFR MDL CODE,YEAR MFR,NAME,STATUS CODE,MODE S CODE HEX,\n\
+            12345,SN1       ,2072738,2001,DELTA AIR LINES INC          ,V,A0B1C2    ,\n\
+            9Z   ,SN2       ,9999999,1975,SMITH JOHN                   ,V,          ,\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("ACFTREF.txt"),
+            "CODE,MFR,MODEL,TYPE-ACFT,NO-ENG,\n\
+            2072738,BOEING                        ,737-832             ,5,02,\n",
+        )
+        .unwrap();
+
+        assert_eq!(SourceFormat::detect(&master), SourceFormat::Faa);
+        assert_eq!(
+            load_rows(&master, &SourceFormat::Faa),
+            vec![(
+                "a0b1c2".to_string(),
+                "N12345".to_string(),
+                Some("BOEING".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn test_column_mapping_toml() {
+        let tmp = fixtures::temp_dir();
+        let dir = tmp.path();
+        let toml_path = dir.join("mapping.toml");
+        std::fs::write(
+            &toml_path,
+            "delimiter = \";\"\n[columns]\nicao24 = \"ModeS\"\nregistration = \"Reg\"\n",
+        )
+        .unwrap();
+        let mapping = ColumnMapping::load(&toml_path).unwrap();
+        assert_eq!(mapping.quote, '"');
+
+        let csv = dir.join("registry.csv");
+        std::fs::write(&csv, "Reg;Type;ModeS\nG-ABCD;A320;40621D\n").unwrap();
+        assert_eq!(
+            load_rows(&csv, &SourceFormat::Mapped(mapping)),
+            vec![("40621d".to_string(), "G-ABCD".to_string(), None)]
+        );
+
+        std::fs::write(
+            &toml_path,
+            "[columns]\nicao24 = \"hex\"\ntailnumber = \"reg\"\n",
+        )
+        .unwrap();
+        assert!(ColumnMapping::load(&toml_path).is_err());
+    }
+}
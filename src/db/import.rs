@@ -0,0 +1,340 @@
+//! Incremental aircraft DB updates.
+//!
+//! [`import_database`] merges a newer OpenSky aircraft CSV, a delta file
+//! with just the changed rows, or another registry (see [`formats`]) into
+//! the existing DB instead of rebuilding it. The file is loaded into a
+//! temporary table, diffed against `aircraft` for the [`ImportReport`], and
+//! upserted, all in one transaction: readers see either the old data or the
+//! new, and a failed import changes nothing.
+
+use super::build::{self, build_database, DbMetadata};
+use super::formats::{self, SourceFormat};
+use super::migrations::{self, SCHEMA_VERSION};
+use super::AIRCRAFT_COLUMNS;
+use color_eyre::eyre::{bail, Result, WrapErr};
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+
+/// How long an import waits for the pollers' lookups to release the DB.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Whether an import file lists every aircraft or only changed ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// A complete registry: aircraft missing from it are removed.
+    Full,
+    /// Only new or changed aircraft; nothing is removed.
+    Delta,
+}
+
+/// Rows an import touched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub added: u64,
+    pub changed: u64,
+    pub removed: u64,
+    pub unchanged: u64,
+}
+
+impl std::fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} added, {} changed, {} removed, {} unchanged",
+            self.added, self.changed, self.removed, self.unchanged
+        )
+    }
+}
+
+/// Newest OpenSky aircraft CSV in `dir`, plain or compressed. OpenSky
+/// dates its dumps in the file name
+/// (`aircraft-database-complete-2025-08.csv`), so the greatest name is the
+/// newest.
+pub fn newest_csv(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+                n.starts_with("aircraft-database")
+                    && [".csv", ".csv.gz", ".csv.zst", ".zip"]
+                        .iter()
+                        .any(|ext| n.ends_with(ext))
+            })
+        })
+        .max()
+}
+
+/// Merges the registry file at `source`, read as `format`, into the DB at
+/// `db_path`: upserts new and changed aircraft and, for
+/// [`ImportMode::Full`], removes those no longer listed. Only the columns
+/// the file has are updated, so a delta with a few columns leaves the
+/// others alone. Records the file in the DB's metadata. Without a DB this
+/// is a [`build_database`].
+///
+/// `progress` is called with the fraction of the file read (0.0–1.0).
+///
+/// # Errors
+///
+/// Fails if the file cannot be read, has no `icao24` column or no rows, or
+/// on any SQLite error. The DB is left as it was.
+pub fn import_database(
+    source: &Path,
+    db_path: &Path,
+    format: &SourceFormat,
+    mode: ImportMode,
+    mut progress: impl FnMut(f32),
+) -> Result<ImportReport> {
+    if !db_path.exists() {
+        let metadata = build_database(source, format, db_path, progress)?;
+        return Ok(ImportReport {
+            added: metadata.row_count,
+            ..Default::default()
+        });
+    }
+
+    let mut conn =
+        Connection::open(db_path).wrap_err_with(|| format!("Cannot open {}", db_path.display()))?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    migrations::migrate(&mut conn)?;
+
+    let tx = conn.transaction()?;
+    tx.execute_batch(&format!(
+        "CREATE TEMP TABLE incoming (icao24 TEXT PRIMARY KEY, {} TEXT)",
+        AIRCRAFT_COLUMNS[1..].join(" TEXT, ")
+    ))?;
+    let loaded = formats::load(&tx, "incoming", source, format, &mut progress)?;
+    let count = |sql: &str| -> rusqlite::Result<u64> { tx.query_row(sql, [], |r| r.get(0)) };
+
+    let incoming = count("SELECT COUNT(*) FROM incoming")?;
+    if incoming == 0 {
+        bail!("{} has no aircraft rows", source.display());
+    }
+
+    let updated = &loaded.columns[1..];
+    let differs = if updated.is_empty() {
+        "0".to_string()
+    } else {
+        updated
+            .iter()
+            .map(|c| format!("a.{c} IS NOT i.{c}"))
+            .collect::<Vec<_>>()
+            .join(" OR ")
+    };
+    let mut report = ImportReport {
+        added: count(
+            "SELECT COUNT(*) FROM incoming i
+            WHERE NOT EXISTS (SELECT 1 FROM aircraft a WHERE a.icao24 = i.icao24)",
+        )?,
+        changed: count(&format!(
+            "SELECT COUNT(*) FROM incoming i JOIN aircraft a ON a.icao24 = i.icao24
+            WHERE {}",
+            differs
+        ))?,
+        ..Default::default()
+    };
+    report.unchanged = incoming - report.added - report.changed;
+
+    if mode == ImportMode::Full {
+        report.removed = tx.execute(
+            "DELETE FROM aircraft WHERE icao24 NOT IN (SELECT icao24 FROM incoming)",
+            [],
+        )? as u64;
+    }
+    let on_conflict = if updated.is_empty() {
+        "DO NOTHING".to_string()
+    } else {
+        let set: Vec<String> = updated
+            .iter()
+            .map(|c| format!("{c} = excluded.{c}"))
+            .collect();
+        format!("DO UPDATE SET {}", set.join(", "))
+    };
+    // `WHERE true` keeps SQLite from parsing ON CONFLICT as a join clause.
+    tx.execute(
+        &format!(
+            "INSERT INTO aircraft ({cols}) SELECT {cols} FROM incoming WHERE true
+            ON CONFLICT(icao24) {on_conflict}",
+            cols = loaded.columns.join(", "),
+        ),
+        [],
+    )?;
+
+    let built_at = tx
+        .query_row(
+            "SELECT value FROM metadata WHERE key = 'built_at'",
+            [],
+            |r| r.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|t| t.parse().ok());
+    let now = chrono::Utc::now().timestamp();
+    let metadata = DbMetadata {
+        schema_version: SCHEMA_VERSION,
+        csv_sha256: loaded.sha256,
+        row_count: count("SELECT COUNT(*) FROM aircraft")?,
+        // A DB from before metadata existed counts as built now.
+        built_at: built_at.unwrap_or(now),
+        imported_at: Some(now),
+        source: source.display().to_string(),
+    };
+    build::write_metadata(&tx, &metadata)?;
+    tx.execute_batch("DROP TABLE incoming")?;
+    tx.commit()?;
+
+    info!("Imported {} into aircraft DB: {}", metadata.source, report);
+    progress(1.0);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{check_database, fixtures, DbStatus};
+    use tempfile::TempDir;
+
+    const AUGUST: &str = "'icao24','registration','typecode','owner'\n\
+        'aaaaaa','N1','B738','Alpha'\n\
+        'bbbbbb','N2','A320','Bravo'\n\
+        'cccccc','N3','E190','Charlie'\n";
+
+    /// A fresh temp dir holding `aircraft.db` built from `AUGUST`.
+    fn fixture() -> TempDir {
+        let dir = fixtures::temp_dir();
+        let csv = dir.path().join("aircraft-database-complete-2025-08.csv");
+        std::fs::write(&csv, AUGUST).unwrap();
+        build_database(
+            &csv,
+            &SourceFormat::OpenSky,
+            &dir.path().join("aircraft.db"),
+            |_| {},
+        )
+        .unwrap();
+        dir
+    }
+
+    fn registration(db: &Path, icao24: &str) -> Option<String> {
+        Connection::open(db)
+            .unwrap()
+            .query_row(
+                "SELECT registration FROM aircraft WHERE icao24 = ?",
+                [icao24],
+                |r| r.get(0),
+            )
+            .ok()
+    }
+
+    #[test]
+    fn test_full_import_reports_and_records_source() {
+        let tmp = fixture();
+        let dir = tmp.path();
+        let db = dir.join("aircraft.db");
+        let september = dir.join("aircraft-database-complete-2025-09.csv");
+        std::fs::write(
+            &september,
+            "'icao24','registration','typecode','owner'\n\
+            'aaaaaa','N1','B738','Alpha'\n\
+            'bbbbbb','N2X','A320','Bravo'\n\
+            'dddddd','N4','B789','Delta'\n",
+        )
+        .unwrap();
+        assert_eq!(newest_csv(dir), Some(september.clone()));
+
+        let report = import_database(
+            &september,
+            &db,
+            &SourceFormat::OpenSky,
+            ImportMode::Full,
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!(
+            report,
+            ImportReport {
+                added: 1,
+                changed: 1,
+                removed: 1,
+                unchanged: 1,
+            }
+        );
+        assert_eq!(registration(&db, "bbbbbb").as_deref(), Some("N2X"));
+        assert_eq!(registration(&db, "cccccc"), None);
+
+        let DbStatus::Ready(metadata) = check_database(&db) else {
+            panic!("import left the DB inconsistent");
+        };
+        assert_eq!(metadata.row_count, 3);
+        assert_eq!(metadata.source, september.display().to_string());
+        assert!(metadata.imported_at.is_some());
+    }
+
+    #[test]
+    fn test_delta_import_keeps_other_rows_and_columns() {
+        let tmp = fixture();
+        let dir = tmp.path();
+        let db = dir.join("aircraft.db");
+        let delta = dir.join("delta.csv");
+        std::fs::write(&delta, "'icao24','registration'\n'CCCCCC','N3Z'\n").unwrap();
+
+        let report = import_database(
+            &delta,
+            &db,
+            &SourceFormat::OpenSky,
+            ImportMode::Delta,
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!((report.added, report.changed, report.removed), (0, 1, 0));
+        assert_eq!(registration(&db, "cccccc").as_deref(), Some("N3Z"));
+        assert_eq!(registration(&db, "aaaaaa").as_deref(), Some("N1"));
+        let typecode: String = Connection::open(&db)
+            .unwrap()
+            .query_row(
+                "SELECT typecode FROM aircraft WHERE icao24 = 'cccccc'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(typecode, "E190");
+
+        // An empty file would otherwise wipe a full import.
+        std::fs::write(&delta, "'icao24','registration'\n").unwrap();
+        assert!(import_database(
+            &delta,
+            &db,
+            &SourceFormat::OpenSky,
+            ImportMode::Full,
+            |_| {}
+        )
+        .is_err());
+        assert_eq!(registration(&db, "aaaaaa").as_deref(), Some("N1"));
+    }
+
+    #[test]
+    fn test_faa_import_keeps_other_countries() {
+        let tmp = fixture();
+        let dir = tmp.path();
+        let db = dir.join("aircraft.db");
+        let master = dir.join("MASTER.txt");
+        std::fs::write(
+            &master,
+            "N-NUMBER,MFR MDL CODE,NAME,MODE S CODE HEX,\n\
+            1X   ,2072738,ALPHA AIR          ,AAAAAA    ,\n\
+            77   ,2072738,ECHO LLC           ,A00001    ,\n",
+        )
+        .unwrap();
+        let format = SourceFormat::detect(&master);
+        assert_eq!(format.default_import_mode(), ImportMode::Delta);
+
+        let report =
+            import_database(&master, &db, &format, format.default_import_mode(), |_| {}).unwrap();
+        assert_eq!((report.added, report.changed, report.removed), (1, 1, 0));
+        assert_eq!(registration(&db, "aaaaaa").as_deref(), Some("N1X"));
+        assert_eq!(registration(&db, "a00001").as_deref(), Some("N77"));
+        // Aircraft the US registry does not list are still there.
+        assert_eq!(registration(&db, "bbbbbb").as_deref(), Some("N2"));
+        assert_eq!(registration(&db, "cccccc").as_deref(), Some("N3"));
+    }
+}
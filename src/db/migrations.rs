@@ -0,0 +1,232 @@
+//! Aircraft DB schema migrations.
+//!
+//! The schema version lives in SQLite's `user_version` header field. Each
+//! [`Migration`] moves the DB from the previous version to its own, in one
+//! transaction together with the version bump, so a crash mid-upgrade
+//! leaves the DB at the last completed version. New schema changes are
+//! appended to [`MIGRATIONS`]; released entries are never edited.
+
+use rusqlite::Connection;
+use std::path::Path;
+use tracing::info;
+
+/// One schema step.
+pub struct Migration {
+    /// `user_version` after this migration; one more than the previous.
+    pub version: u32,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// Every migration, oldest first.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "aircraft identity table and build metadata",
+        // `IF NOT EXISTS` adopts DBs built before migrations existed.
+        sql: "CREATE TABLE IF NOT EXISTS aircraft (
+                icao24 TEXT PRIMARY KEY,
+                manufacturerName TEXT,
+                model TEXT,
+                operator TEXT,
+                operatorCallsign TEXT,
+                owner TEXT,
+                registration TEXT,
+                typecode TEXT
+            );
+            CREATE TABLE IF NOT EXISTS metadata (key TEXT PRIMARY KEY, value TEXT NOT NULL);",
+    },
+    Migration {
+        version: 2,
+        description: "airframe columns from the OpenSky CSV",
+        sql: "ALTER TABLE aircraft ADD COLUMN manufacturerIcao TEXT;
+            ALTER TABLE aircraft ADD COLUMN serialNumber TEXT;
+            ALTER TABLE aircraft ADD COLUMN built TEXT;
+            ALTER TABLE aircraft ADD COLUMN engines TEXT;
+            ALTER TABLE aircraft ADD COLUMN categoryDescription TEXT;
+            ALTER TABLE aircraft ADD COLUMN icaoAircraftClass TEXT;",
+    },
+    Migration {
+        version: 3,
+        description: "operator and registration columns from the OpenSky CSV",
+        sql: "ALTER TABLE aircraft ADD COLUMN operatorIcao TEXT;
+            ALTER TABLE aircraft ADD COLUMN operatorIata TEXT;
+            ALTER TABLE aircraft ADD COLUMN country TEXT;
+            ALTER TABLE aircraft ADD COLUMN registered TEXT;
+            ALTER TABLE aircraft ADD COLUMN regUntil TEXT;
+            ALTER TABLE aircraft ADD COLUMN status TEXT;",
+    },
+];
+
+/// Schema version this build of Redwood reads and writes.
+pub const SCHEMA_VERSION: u32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+/// Failure to bring a DB up to [`SCHEMA_VERSION`].
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The DB could not be opened or its version read.
+    Open(rusqlite::Error),
+    /// The DB was written by a newer Redwood with a schema this one does
+    /// not know.
+    TooNew { found: u32 },
+    /// A migration failed and was rolled back; the DB stays at the previous
+    /// version.
+    Failed {
+        version: u32,
+        source: rusqlite::Error,
+    },
+}
+
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Open(e) => write!(f, "cannot open aircraft DB: {}", e),
+            Self::TooNew { found } => write!(
+                f,
+                "aircraft DB schema v{} is newer than supported v{}",
+                found, SCHEMA_VERSION
+            ),
+            Self::Failed { version, source } => {
+                write!(
+                    f,
+                    "aircraft DB migration to v{} failed: {}",
+                    version, source
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Open(e) | Self::Failed { source: e, .. } => Some(e),
+            Self::TooNew { .. } => None,
+        }
+    }
+}
+
+/// The DB's schema version.
+pub fn schema_version(conn: &Connection) -> rusqlite::Result<u32> {
+    conn.query_row("PRAGMA user_version", [], |r| r.get(0))
+}
+
+/// Applies every migration newer than the DB's version, in order, and
+/// records the result in `metadata` too. Returns the version reached.
+pub fn migrate(conn: &mut Connection) -> Result<u32, MigrationError> {
+    let mut current = schema_version(conn).map_err(MigrationError::Open)?;
+    if current > SCHEMA_VERSION {
+        return Err(MigrationError::TooNew { found: current });
+    }
+
+    let from = current;
+    for migration in MIGRATIONS.iter().filter(|m| m.version > from) {
+        let failed = |source| MigrationError::Failed {
+            version: migration.version,
+            source,
+        };
+        let tx = conn.transaction().map_err(failed)?;
+        tx.execute_batch(migration.sql).map_err(failed)?;
+        tx.execute(
+            "UPDATE metadata SET value = ? WHERE key = 'schema_version'",
+            [migration.version.to_string()],
+        )
+        .map_err(failed)?;
+        // PRAGMA takes no bound parameters; the version is our own constant.
+        tx.execute_batch(&format!("PRAGMA user_version = {}", migration.version))
+            .map_err(failed)?;
+        tx.commit().map_err(failed)?;
+        if from > 0 {
+            info!(
+                "Migrated aircraft DB to v{}: {}",
+                migration.version, migration.description
+            );
+        }
+        current = migration.version;
+    }
+    Ok(current)
+}
+
+/// Opens the DB at `path` read-write and migrates it. A missing file is
+/// left missing (returns `Ok(None)`) for the first-run build to create.
+pub fn migrate_database(path: &Path) -> Result<Option<u32>, MigrationError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut conn = Connection::open(path).map_err(MigrationError::Open)?;
+    migrate(&mut conn).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Columns of the `aircraft` table, in order.
+    fn columns(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT name FROM pragma_table_info('aircraft')")
+            .unwrap();
+        let names = stmt.query_map([], |r| r.get(0)).unwrap();
+        names.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn test_upgrades_v1_fixture() {
+        // A DB as built by the first release: eight columns, metadata, v1.
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE aircraft (icao24 TEXT PRIMARY KEY, manufacturerName TEXT,
+                model TEXT, operator TEXT, operatorCallsign TEXT, owner TEXT,
+                registration TEXT, typecode TEXT);
+            CREATE TABLE metadata (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+            INSERT INTO aircraft VALUES ('abc123', 'Boeing', '737-800', 'United', 'UNITED',
+                '', 'N123AB', 'B738');
+            INSERT INTO metadata VALUES ('schema_version', '1');
+            PRAGMA user_version = 1;",
+        )
+        .unwrap();
+
+        assert_eq!(migrate(&mut conn).unwrap(), SCHEMA_VERSION);
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        // The importer writes exactly the columns the migrations create.
+        assert_eq!(columns(&conn), crate::db::AIRCRAFT_COLUMNS);
+
+        // Existing rows survive; the new columns start empty.
+        let (reg, built): (String, Option<String>) = conn
+            .query_row(
+                "SELECT registration, built FROM aircraft WHERE icao24 = 'abc123'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((reg.as_str(), built), ("N123AB", None));
+        let recorded: String = conn
+            .query_row(
+                "SELECT value FROM metadata WHERE key = 'schema_version'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(recorded, SCHEMA_VERSION.to_string());
+
+        // Running again is a no-op.
+        assert_eq!(migrate(&mut conn).unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrations_are_ordered_and_refuse_newer_dbs() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as u32 + 1, "{}", migration.description);
+        }
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(migrate(&mut conn).unwrap(), SCHEMA_VERSION);
+
+        conn.execute_batch(&format!("PRAGMA user_version = {}", SCHEMA_VERSION + 1))
+            .unwrap();
+        assert!(matches!(
+            migrate(&mut conn),
+            Err(MigrationError::TooNew { .. })
+        ));
+    }
+}
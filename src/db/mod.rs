@@ -0,0 +1,143 @@
+//! SQLite aircraft database and enrichment for the Redwood flight tracker.
+//!
+//! This module provides:
+//! - **[`init_database`]** — Builds `opensky_aircraft.db` from the aircraft CSV on a
+//!   background thread, sending [`Event::DbProgress`], [`Event::DbDone`], or
+//!   [`Event::DbError`] so the UI can show first-run progress. The build is
+//!   atomic and records its source in a `metadata` table, which
+//!   [`check_database`] verifies at startup.
+//! - **[`import_in_background`]** — Merges a newer CSV or a delta file into
+//!   the existing DB ([`import_database`]), reporting added, changed and
+//!   removed aircraft through [`Event::DbImported`].
+//! - **[`formats`]** — Reads OpenSky CSVs, the FAA registry and
+//!   [`ColumnMapping`]-described files, plain or as `.gz`, `.zst` or `.zip`.
+//! - **[`migrations`]** — Versioned schema upgrades ([`migrate_database`]),
+//!   applied to an existing DB at startup and to every new build.
+//! - **[`AircraftRegistry`]** — Looks up flights by ICAO24 in the DB and fills
+//!   in manufacturer, model, operator, registration, etc., over one long-lived
+//!   connection with an LRU cache. [`SharedRegistry`] shares one between the
+//!   pollers and is intended to be called from a blocking task (e.g.
+//!   `spawn_blocking`) to avoid blocking the async runtime.
+
+pub mod build;
+pub mod formats;
+pub mod import;
+pub mod migrations;
+pub mod registry;
+
+pub use build::{build_database, check_database, DbMetadata, DbStatus, AIRCRAFT_COLUMNS, CSV_PATH};
+pub use formats::{ColumnMapping, SourceFormat};
+pub use import::{import_database, newest_csv, ImportMode, ImportReport};
+pub use migrations::{migrate_database, MigrationError, SCHEMA_VERSION};
+pub use registry::{AircraftInfo, AircraftRegistry, RegistryError, SharedRegistry};
+
+use crate::events::Event;
+use crate::icao;
+use crate::models::Flight;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+
+/// Aircraft database file, relative to the working directory.
+pub const DB_PATH: &str = "opensky_aircraft.db";
+/// Directory searched for aircraft CSVs, relative to the working directory.
+pub const DATA_DIR: &str = "data";
+
+/// Builds the aircraft SQLite database from the CSV and notifies via `tx`.
+///
+/// Spawns a thread that runs [`build_database`] from the newest CSV in
+/// [`DATA_DIR`] (see [`newest_csv`], falling back to [`CSV_PATH`]) into
+/// [`DB_PATH`], sending [`Event::DbProgress`] (0.0–1.0, by bytes read) as
+/// it goes, then [`Event::DbDone`] or [`Event::DbError`]. The DB is built
+/// in a temporary file and renamed into place, so an existing DB stays
+/// usable while it is rebuilt and after a failed build.
+///
+/// The caller (e.g. [`App::new`](crate::app::App::new)) should pass the sending half of an
+/// `mpsc::channel` and receive these events on the main thread (e.g. in [`App::on_tick`](crate::app::App::on_tick)).
+///
+/// # Arguments
+///
+/// * `tx` - Sender for progress/done/error events; consumed by the spawned thread.
+pub fn init_database(tx: Sender<Event>) {
+    std::thread::spawn(move || {
+        let progress = |p| {
+            let _ = tx.send(Event::DbProgress(p));
+        };
+        let csv = newest_csv(Path::new(DATA_DIR)).unwrap_or_else(|| PathBuf::from(CSV_PATH));
+        let format = SourceFormat::detect(&csv);
+        match build_database(&csv, &format, Path::new(DB_PATH), progress) {
+            Ok(_) => {
+                let _ = tx.send(Event::DbDone);
+            }
+            Err(e) => {
+                let _ = tx.send(Event::DbError(format!("{:#}", e)));
+            }
+        }
+    });
+}
+
+/// Imports `source`, in the format its name suggests (see
+/// [`SourceFormat::detect`]), into [`DB_PATH`] on a background thread (see
+/// [`import_database`]), sending [`Event::DbProgress`] as it reads the file,
+/// then [`Event::DbImported`] or [`Event::DbError`].
+pub fn import_in_background(source: PathBuf, mode: ImportMode, tx: Sender<Event>) {
+    std::thread::spawn(move || {
+        let progress = |p| {
+            let _ = tx.send(Event::DbProgress(p));
+        };
+        let format = SourceFormat::detect(&source);
+        match import_database(&source, Path::new(DB_PATH), &format, mode, progress) {
+            Ok(report) => {
+                let _ = tx.send(Event::DbImported(report));
+            }
+            Err(e) => {
+                let _ = tx.send(Event::DbError(format!("{:#}", e)));
+            }
+        }
+    });
+}
+
+/// Fills `origin_country` from the ICAO24 address block (see
+/// [`icao::lookup`]) for flights whose feed did not report one, such as
+/// local receivers.
+pub fn fill_origin_country(flights: &mut [Flight]) {
+    for flight in flights {
+        if !flight.non_icao
+            && (flight.origin_country.is_empty() || flight.origin_country == "Unknown")
+        {
+            if let Some(country) = icao::lookup(&flight.icao24).and_then(|block| block.country) {
+                flight.origin_country = country.to_string();
+            }
+        }
+    }
+}
+
+/// Fixtures shared by the DB tests.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use rusqlite::Connection;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// A fresh temp dir, removed when dropped.
+    pub fn temp_dir() -> TempDir {
+        tempfile::Builder::new()
+            .prefix("redwood-")
+            .tempdir()
+            .unwrap()
+    }
+
+    /// Creates a current-schema DB at `path` through the migrations, holding
+    /// a Boeing 737-800 for each (icao24, registration) in `rows`.
+    pub fn aircraft_db(path: &Path, rows: &[(&str, &str)]) {
+        let mut conn = Connection::open(path).unwrap();
+        super::migrations::migrate(&mut conn).unwrap();
+        for (icao24, registration) in rows {
+            conn.execute(
+                "INSERT INTO aircraft (icao24, manufacturerName, model, operator, registration, typecode)
+                VALUES (?1, 'Boeing', '737-800', '', ?2, 'B738')",
+                [icao24, registration],
+            )
+            .unwrap();
+        }
+    }
+}
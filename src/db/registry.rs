@@ -0,0 +1,366 @@
+//! Cached aircraft identity lookups.
+//!
+//! [`AircraftRegistry`] keeps one read-only connection to the aircraft DB
+//! open for the life of the app and remembers every ICAO24 it has looked up,
+//! found or not, in an LRU cache. A poll of a few hundred aircraft therefore
+//! costs a handful of hash lookups once the sky has been seen, and at most
+//! one `IN (...)` query per [`MAX_BATCH`] new addresses otherwise.
+
+use crate::models::Flight;
+use lru::LruCache;
+use rusqlite::{Connection, OpenFlags};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// ICAO24 addresses remembered by default, hits and misses together.
+pub const DEFAULT_CACHE_SIZE: usize = 8192;
+/// Addresses per `IN (...)` query, well under SQLite's bound-parameter limit.
+pub const MAX_BATCH: usize = 500;
+
+/// Identity of one aircraft from the DB. Empty columns are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AircraftInfo {
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    pub operator: Option<String>,
+    pub operator_callsign: Option<String>,
+    pub registration: Option<String>,
+    pub typecode: Option<String>,
+}
+
+impl AircraftInfo {
+    /// Copies the identity fields onto `flight`.
+    pub fn apply_to(&self, flight: &mut Flight) {
+        flight.manufacturer.clone_from(&self.manufacturer);
+        flight.model.clone_from(&self.model);
+        flight.operator.clone_from(&self.operator);
+        flight.operator_callsign.clone_from(&self.operator_callsign);
+        flight.registration.clone_from(&self.registration);
+        flight.aircraft_type.clone_from(&self.typecode);
+    }
+}
+
+/// Failure to open or query the aircraft DB.
+#[derive(Debug)]
+pub enum RegistryError {
+    /// The DB file does not exist (yet); first-run init may still be running.
+    Missing(PathBuf),
+    /// The DB exists but could not be opened.
+    Open(rusqlite::Error),
+    /// A lookup query failed, e.g. on a schema mismatch.
+    Query(rusqlite::Error),
+}
+
+impl std::fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(path) => write!(f, "aircraft DB {} does not exist", path.display()),
+            Self::Open(e) => write!(f, "cannot open aircraft DB: {}", e),
+            Self::Query(e) => write!(f, "aircraft DB lookup failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Missing(_) => None,
+            Self::Open(e) | Self::Query(e) => Some(e),
+        }
+    }
+}
+
+/// Aircraft identity lookups over one long-lived connection with an LRU
+/// cache of hits and misses.
+pub struct AircraftRegistry {
+    conn: Connection,
+    /// `None` records an ICAO24 known not to be in the DB.
+    cache: LruCache<String, Option<AircraftInfo>>,
+}
+
+impl AircraftRegistry {
+    /// Opens the DB at `path` read-only with a [`DEFAULT_CACHE_SIZE`] cache.
+    ///
+    /// # Errors
+    ///
+    /// [`RegistryError::Missing`] if the file does not exist,
+    /// [`RegistryError::Open`] if SQLite cannot open it.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, RegistryError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(RegistryError::Missing(path.to_path_buf()));
+        }
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(RegistryError::Open)?;
+        Ok(Self {
+            conn,
+            cache: LruCache::new(NonZeroUsize::new(DEFAULT_CACHE_SIZE).unwrap()),
+        })
+    }
+
+    /// Replaces the cache with an empty one holding up to `size` entries.
+    pub fn with_cache_size(mut self, size: usize) -> Self {
+        self.cache = LruCache::new(NonZeroUsize::new(size.max(1)).unwrap());
+        self
+    }
+
+    /// Number of cached ICAO24 addresses, hits and misses.
+    pub fn cached(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Looks up one aircraft.
+    ///
+    /// # Errors
+    ///
+    /// [`RegistryError::Query`] if the query fails.
+    pub fn get(&mut self, icao24: &str) -> Result<Option<AircraftInfo>, RegistryError> {
+        let key = normalize(icao24);
+        Ok(self.lookup(std::slice::from_ref(&key))?.remove(&key))
+    }
+
+    /// Looks up many aircraft at once, keyed by lowercase ICAO24. Addresses
+    /// not in the DB are absent from the result.
+    ///
+    /// Cached addresses are answered from the cache; the rest are fetched in
+    /// `IN (...)` batches of up to [`MAX_BATCH`] and cached, misses included.
+    ///
+    /// # Errors
+    ///
+    /// [`RegistryError::Query`] if a query fails. Addresses fetched before
+    /// the failure stay cached.
+    pub fn lookup(
+        &mut self,
+        icao24s: &[String],
+    ) -> Result<HashMap<String, AircraftInfo>, RegistryError> {
+        let mut found = HashMap::new();
+        let mut missing = Vec::new();
+        for icao in icao24s {
+            let key = normalize(icao);
+            match self.cache.get(&key) {
+                Some(Some(info)) => {
+                    found.insert(key, info.clone());
+                }
+                Some(None) => {}
+                None if !missing.contains(&key) => missing.push(key),
+                None => {}
+            }
+        }
+
+        for batch in missing.chunks(MAX_BATCH) {
+            let rows = self.query(batch).map_err(RegistryError::Query)?;
+            for key in batch {
+                let info = rows.get(key).cloned();
+                if let Some(info) = &info {
+                    found.insert(key.clone(), info.clone());
+                }
+                self.cache.put(key.clone(), info);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Fills the identity fields of every flight found in the DB. Non-ICAO
+    /// targets are not looked up.
+    ///
+    /// # Errors
+    ///
+    /// [`RegistryError::Query`] if a query fails; flights are left as-is.
+    pub fn decorate(&mut self, flights: &mut [Flight]) -> Result<(), RegistryError> {
+        let icao24s: Vec<String> = flights
+            .iter()
+            .filter(|f| !f.non_icao)
+            .map(|f| f.icao24.clone())
+            .collect();
+        let found = self.lookup(&icao24s)?;
+        for flight in flights.iter_mut().filter(|f| !f.non_icao) {
+            if let Some(info) = found.get(&normalize(&flight.icao24)) {
+                info.apply_to(flight);
+            }
+        }
+        Ok(())
+    }
+
+    fn query(&self, keys: &[String]) -> rusqlite::Result<HashMap<String, AircraftInfo>> {
+        let placeholders = vec!["?"; keys.len()].join(",");
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT icao24, manufacturerName, model, operator, operatorCallsign, registration, typecode
+            FROM aircraft WHERE icao24 IN ({})",
+            placeholders
+        ))?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(keys), |row| {
+            let text = |i: usize| -> rusqlite::Result<Option<String>> {
+                Ok(row
+                    .get::<_, Option<String>>(i)?
+                    .filter(|s| !s.trim().is_empty()))
+            };
+            Ok((
+                row.get::<_, String>(0)?,
+                AircraftInfo {
+                    manufacturer: text(1)?,
+                    model: text(2)?,
+                    operator: text(3)?,
+                    operator_callsign: text(4)?,
+                    registration: text(5)?,
+                    typecode: text(6)?,
+                },
+            ))
+        })?;
+        rows.collect()
+    }
+}
+
+/// An [`AircraftRegistry`] shared by every poller, opened on first use.
+///
+/// Until the DB exists (first-run init) flights pass through unchanged. A
+/// failed query is logged and drops the connection so the next poll
+/// reopens it.
+#[derive(Clone)]
+pub struct SharedRegistry {
+    path: PathBuf,
+    inner: Arc<Mutex<Option<AircraftRegistry>>>,
+}
+
+impl Default for SharedRegistry {
+    fn default() -> Self {
+        Self::new(super::DB_PATH)
+    }
+}
+
+impl SharedRegistry {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            inner: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Fills `origin_country` from the address block where missing, then
+    /// enriches flights from the DB. Never fails: DB problems are logged and
+    /// the flights returned without DB data.
+    ///
+    /// Blocks on SQLite; call it from `spawn_blocking`.
+    pub fn decorate(&self, mut flights: Vec<Flight>) -> Vec<Flight> {
+        super::fill_origin_country(&mut flights);
+
+        let mut guard = match self.inner.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if guard.is_none() {
+            match AircraftRegistry::open(&self.path) {
+                Ok(registry) => *guard = Some(registry),
+                Err(RegistryError::Missing(_)) => return flights,
+                Err(e) => {
+                    warn!("{}", e);
+                    return flights;
+                }
+            }
+        }
+        if let Some(registry) = guard.as_mut() {
+            if let Err(e) = registry.decorate(&mut flights) {
+                warn!("{}", e);
+                *guard = None;
+            }
+        }
+        flights
+    }
+
+    /// Closes the connection and forgets the cache, e.g. after the DB file
+    /// was rebuilt; the next call reopens it.
+    pub fn reset(&self) {
+        if let Ok(mut guard) = self.inner.lock() {
+            *guard = None;
+        }
+    }
+}
+
+/// Cache and DB key for an ICAO24 address.
+fn normalize(icao24: &str) -> String {
+    icao24.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::fixtures;
+
+    #[test]
+    fn test_batched_lookup_caches_hits_and_misses() {
+        let dir = fixtures::temp_dir();
+        let path = dir.path().join("aircraft.db");
+        fixtures::aircraft_db(&path, &[("abc123", "N123AB"), ("def456", "N456CD")]);
+        let mut registry = AircraftRegistry::open(&path).unwrap();
+
+        let keys: Vec<String> = ["ABC123", "def456", "ffffff", "abc123"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let found = registry.lookup(&keys).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found["abc123"].registration.as_deref(), Some("N123AB"));
+        // Empty columns come back as None.
+        assert_eq!(found["abc123"].operator, None);
+        assert_eq!(registry.cached(), 3);
+
+        // A row added later is not seen: the miss is cached.
+        Connection::open(&path)
+            .unwrap()
+            .execute(
+                "INSERT INTO aircraft (icao24, registration) VALUES ('ffffff', 'N999')",
+                [],
+            )
+            .unwrap();
+        assert_eq!(registry.get("ffffff").unwrap(), None);
+        assert_eq!(
+            AircraftRegistry::open(&path)
+                .unwrap()
+                .get("ffffff")
+                .unwrap()
+                .and_then(|i| i.registration),
+            Some("N999".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decorate_and_errors() {
+        let dir = fixtures::temp_dir();
+        let path = dir.path().join("aircraft.db");
+        fixtures::aircraft_db(&path, &[("abc123", "N123AB")]);
+        let mut flights = vec![
+            Flight {
+                icao24: "abc123".to_string(),
+                ..Default::default()
+            },
+            Flight {
+                icao24: "000001".to_string(),
+                ..Default::default()
+            },
+        ];
+        AircraftRegistry::open(&path)
+            .unwrap()
+            .with_cache_size(1)
+            .decorate(&mut flights)
+            .unwrap();
+        assert_eq!(flights[0].registration.as_deref(), Some("N123AB"));
+        assert_eq!(flights[0].aircraft_type.as_deref(), Some("B738"));
+        assert_eq!(flights[1].registration, None);
+
+        assert!(matches!(
+            AircraftRegistry::open(path.with_extension("missing")),
+            Err(RegistryError::Missing(_))
+        ));
+
+        // A DB without the expected table fails with a typed error.
+        let conn = Connection::open(&path).unwrap();
+        conn.execute("DROP TABLE aircraft", []).unwrap();
+        let err = AircraftRegistry::open(&path)
+            .unwrap()
+            .get("abc123")
+            .unwrap_err();
+        assert!(matches!(err, RegistryError::Query(_)));
+    }
+}
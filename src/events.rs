@@ -6,6 +6,9 @@
 //! The main loop in `main.rs` receives events via [`EventHandler::next`] and
 //! other tasks (e.g. the API poller) send events via [`EventHandler::tx`].
 
+use crate::api::schedule::PollSchedule;
+use crate::api::{RateLimit, SourceHealth};
+use crate::db::ImportReport;
 use crate::models::Flight;
 use crossterm::event::{self, Event as CrosstermEvent, KeyEvent};
 use std::time::{Duration, Instant};
@@ -20,21 +23,35 @@ pub enum Event {
     Tick,
     /// User key press from the terminal.
     Input(KeyEvent),
-    /// New flight data from the API poller (or a failed fetch).
+    /// New flight data from one flight source (or a failed fetch). With
+    /// several sources configured, each sends its own updates.
     FlightUpdate {
         /// Flights in the area; may be empty on API failure.
         flights: Vec<Flight>,
-        /// Number of flights that were enriched with DB data.
-        db_hits: usize,
         /// When this update was produced.
         timestamp: std::time::Instant,
         /// Whether the API request succeeded.
         is_success: bool,
+        /// Name of the source that produced this update.
+        source: String,
+        /// Health of that source after this fetch.
+        health: SourceHealth,
+        /// API credit state of that source, if it is rate limited.
+        rate_limit: Option<RateLimit>,
     },
-    /// Database initialization progress (0.0 to 1.0).
+    /// A source's poller has scheduled its next fetch.
+    PollScheduled {
+        /// Name of the source.
+        source: String,
+        /// Effective interval and time of the next poll.
+        schedule: PollSchedule,
+    },
+    /// Database initialization or import progress (0.0 to 1.0).
     DbProgress(f32),
     /// Database initialization completed successfully.
     DbDone,
+    /// An import into the existing database completed.
+    DbImported(ImportReport),
     /// Database initialization failed; payload is the error message.
     DbError(String),
 }
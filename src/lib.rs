@@ -1,9 +1,16 @@
+pub mod alerts;
 pub mod api;
 pub mod app;
+pub mod classify;
 pub mod config;
 pub mod db;
 pub mod events;
+pub mod icao;
 pub mod location;
 pub mod logging;
+pub mod merge;
 pub mod models;
+pub mod phase;
+pub mod tracks;
 pub mod ui;
+pub mod units;
@@ -7,7 +7,7 @@
 
 use ipgeolocate::{Locator, Service};
 use tracing::Instrument;
-use tracing::{error, info, info_span, instrument, warn};
+use tracing::{error, info, warn};
 
 const FALLBACK_COORDS: (f64, f64) = (37.7749, -122.4194);
 
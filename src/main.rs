@@ -3,31 +3,43 @@
 //! This module initializes the application, sets up the terminal,
 //! creates the event handler, and starts the background API poller.
 //! It also handles user input and updates the application state.
-use color_eyre::Result;
+use color_eyre::{eyre::bail, Result};
 use crossterm::event::KeyCode;
 use ratatui::{backend::CrosstermBackend, Terminal};
 use redwood_tui::{
-    api::FlightProvider,
+    api::{
+        schedule::{self, PollOutcome, PollSchedule, PollScheduler},
+        FlightProvider, SearchArea,
+    },
     app::{App, ViewMode},
-    db,
+    db::{import_database, ColumnMapping, ImportMode, SharedRegistry, SourceFormat, DB_PATH},
     events::{Event, EventHandler},
-    logging, ui,
+    logging,
+    tracks::TrackStore,
+    ui,
 };
-use std::{io, time::Duration, time::Instant};
-use tracing::info;
+use std::{
+    io::{self, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
 
 /// Application entry point.
 ///
-/// 1. **Startup**: Load config, initialize logging, install panic hook and
-///    color_eyre. Set up the terminal for TUI mode.
+/// 1. **Startup**: Initialize logging, install panic hook and color_eyre.
+///    `redwood-tui db ...` runs a [database command](run_db_command) and
+///    exits; otherwise load config and set up the terminal for TUI mode.
 /// 2. **Location**: Use IP geolocation or manual config for user coordinates.
 /// 3. **App & events**: Create [`App`] and an [`EventHandler`] (tick rate 150 ms).
-/// 4. **Background poller**: Spawn a task that periodically fetches flights
-///    from OpenSky, enriches them via the local DB, and sends
-///    [`Event::FlightUpdate`] on the event channel.
-/// 5. **Main loop**: Draw the UI, then block on the next event. Handle input
-///    (view switch, quit, delegate to [`App::handle_key`]), ticks
-///    ([`App::on_tick`]), and flight updates (sort by distance, update app state).
+/// 4. **Background pollers**: Build a [`FlightProvider`] for each configured
+///    source and spawn a task per source that periodically polls it (fetch,
+///    enrich via the local DB) and sends the resulting [`Event::FlightUpdate`].
+/// 5. **Main loop**: Draw the UI (ringing the bell if an alert was raised),
+///    then block on the next event. Handle input (view switch, quit, delegate
+///    to [`App::handle_key`]), ticks ([`App::on_tick`]), and flight updates
+///    ([`App::on_flight_update`] merges sources, raises alerts and sorts by
+///    distance).
 /// 6. **Shutdown**: Restore terminal and exit.
 ///
 /// # Errors
@@ -41,11 +53,17 @@ use tracing::info;
 /// before the default panic handler runs.
 #[tokio::main]
 async fn main() -> Result<()> {
-    let config = redwood_tui::config::Config::load();
     let _log_guard = logging::initialize_logging();
     install_panic_hook();
     color_eyre::install()?;
 
+    // Before loading the config, which writes config.toml if it is missing.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("db") {
+        return run_db_command(&args[1..]);
+    }
+    let config = redwood_tui::config::Config::load();
+
     info!("Redwood TUI starting up...");
 
     let mut terminal = setup_terminal()?;
@@ -55,9 +73,12 @@ async fn main() -> Result<()> {
     } else {
         (config.location.manual_lat, config.location.manual_lon)
     };
+    let registry = SharedRegistry::default();
     let mut app = App::new();
+    app.registry = registry.clone();
     app.user_coords = coords;
     app.config = config.clone();
+    app.tracks = TrackStore::new(&config.tracks);
     app.tracking_region = format!("LAT: {:.2}, LON: {:.2}", coords.0, coords.1);
     let events = EventHandler::new(150);
 
@@ -67,51 +88,29 @@ async fn main() -> Result<()> {
         _ => ViewMode::Spotter,
     };
 
-    // Background API Poller
-    let api_tx = events.tx.clone();
-    let poll_interval = config.api.poll_interval_seconds;
-    let radius = config.location.detection_radius;
-    let user_lat = coords.0;
-    let user_lon = coords.1;
-    tokio::spawn(async move {
-        let provider = FlightProvider::new();
-        loop {
-            // SF Coordinates
-            match provider.fetch_overhead(user_lat, user_lon, radius).await {
-                Ok(flights) => {
-                    // offload DB lookup to blocking thread
-                    let enriched =
-                        tokio::task::spawn_blocking(move || db::decorate_flights(flights))
-                            .await
-                            .unwrap_or_default();
-
-                    let hits = enriched.iter().filter(|f| f.registration.is_some()).count();
-
-                    let _ = api_tx.send(Event::FlightUpdate {
-                        flights: enriched,
-                        db_hits: hits,
-                        timestamp: Instant::now(),
-                        is_success: true,
-                    });
-                }
-                Err(e) => {
-                    tracing::error!("API Fetch failed: {}", e);
-                    let _ = api_tx.send(Event::FlightUpdate {
-                        flights: Vec::new(),
-                        db_hits: 0,
-                        timestamp: std::time::Instant::now(),
-                        is_success: false,
-                    });
-                }
-            }
-            tokio::time::sleep(Duration::from_secs(poll_interval)).await;
-        }
-    });
+    // Background API Pollers, one per configured source
+    let area = SearchArea {
+        lat: coords.0,
+        lon: coords.1,
+        radius_km: config.location.detection_radius,
+    };
+    let default_interval = Duration::from_secs(config.api.poll_interval_seconds);
+    for source in std::iter::once(&config.api.source).chain(&config.api.extra_sources) {
+        spawn_poller(
+            FlightProvider::from_config(source).with_registry(registry.clone()),
+            area,
+            default_interval,
+            events.tx.clone(),
+        );
+    }
 
     // Main loop
     let mut event_handler = events;
     while !app.should_quit {
         terminal.draw(|f| ui::render(f, &app))?;
+        if std::mem::take(&mut app.bell_pending) {
+            ring_bell(&mut terminal)?;
+        }
 
         if let Some(event) = event_handler.next().await {
             match event {
@@ -121,6 +120,7 @@ async fn main() -> Result<()> {
                         KeyCode::Char('2') => app.view_mode = ViewMode::Spotter,
                         KeyCode::Char('3') => app.view_mode = ViewMode::Radar,
                         KeyCode::Char('4') => app.view_mode = ViewMode::Settings,
+                        KeyCode::Char('5') => app.view_mode = ViewMode::Alerts,
                         KeyCode::Char('q') => app.should_quit = true,
                         _ => app.handle_key(key), // Pass other keys to app logic
                     }
@@ -128,27 +128,18 @@ async fn main() -> Result<()> {
                 Event::Tick => app.on_tick(),
                 Event::FlightUpdate {
                     flights,
-                    db_hits,
                     timestamp,
                     is_success,
-                } => {
-                    if !app.is_initializing {
-                        app.last_update_success = is_success;
-                        let mut sorted = flights;
-                        let (u_lat, u_lon) = app.user_coords;
-                        // Sort nearest to farthest
-                        sorted.sort_by(|a, b| {
-                            a.distance_from(u_lat, u_lon)
-                                .partial_cmp(&b.distance_from(u_lat, u_lon))
-                                .unwrap_or(std::cmp::Ordering::Equal)
-                        });
-
-                        if is_success {
-                            app.flights = sorted;
-                            app.db_match_count = db_hits;
-                            app.last_update = Some(timestamp);
-                        }
-                    }
+                    source,
+                    health,
+                    rate_limit,
+                } if !app.is_initializing => {
+                    app.on_flight_update(
+                        source, flights, health, rate_limit, is_success, timestamp,
+                    );
+                }
+                Event::PollScheduled { source, schedule } => {
+                    app.poll_schedules.insert(source, schedule);
                 }
                 _ => {}
             }
@@ -159,6 +150,100 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Runs `redwood-tui db import [--full | --delta] [--mapping <toml>] <path>`
+/// without starting the TUI: merges an aircraft registry into the aircraft
+/// DB and prints what changed. The format is guessed from the file name
+/// (see [`SourceFormat::detect`]) unless `--mapping` gives a
+/// [`ColumnMapping`]. `--full` removes aircraft the file does not list and
+/// `--delta` keeps them; without either, only an OpenSky dump is imported
+/// in full (see [`SourceFormat::default_import_mode`]).
+///
+/// # Errors
+///
+/// Returns an error on unknown arguments or if the import fails; the DB is
+/// then unchanged.
+fn run_db_command(args: &[String]) -> Result<()> {
+    const USAGE: &str = "usage: redwood-tui db import [--full | --delta] [--mapping <toml>] <path>";
+    let Some((command, rest)) = args.split_first() else {
+        bail!(USAGE);
+    };
+    let mut mode = None;
+    let mut mapping = None;
+    let mut paths = Vec::new();
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--full" => mode = Some(ImportMode::Full),
+            "--delta" => mode = Some(ImportMode::Delta),
+            "--mapping" => match rest.next() {
+                Some(toml) => mapping = Some(ColumnMapping::load(Path::new(toml))?),
+                None => bail!(USAGE),
+            },
+            _ => paths.push(arg),
+        }
+    }
+    let (true, [path]) = (command == "import", paths.as_slice()) else {
+        bail!(USAGE);
+    };
+    let format = mapping.map_or_else(
+        || SourceFormat::detect(Path::new(path)),
+        SourceFormat::Mapped,
+    );
+    let mode = mode.unwrap_or_else(|| format.default_import_mode());
+    eprintln!("Reading {} as {} ({:?} import)", path, format.label(), mode);
+
+    let mut shown = None;
+    let report = import_database(Path::new(path), Path::new(DB_PATH), &format, mode, |p| {
+        let percent = (p * 100.0) as u32;
+        if shown != Some(percent) {
+            eprint!("\rImporting {}... {:3}%", path, percent);
+            shown = Some(percent);
+        }
+    })?;
+    eprintln!();
+    println!("{}", report);
+    Ok(())
+}
+
+/// Spawns the background task that polls one flight source forever.
+///
+/// Each poll's [`Event::FlightUpdate`] is sent on `tx`, followed by an
+/// [`Event::PollScheduled`] with the delay a [`PollScheduler`] picked from
+/// the outcome. The base cadence is the source's own, or `default_interval`
+/// if it has none.
+fn spawn_poller(
+    provider: FlightProvider,
+    area: SearchArea,
+    default_interval: Duration,
+    tx: tokio::sync::mpsc::UnboundedSender<Event>,
+) {
+    let mut scheduler = PollScheduler::new(provider.poll_interval(default_interval));
+    info!(source = %provider.name(), "starting flight source poller");
+    tokio::spawn(async move {
+        loop {
+            let update = provider.poll(&area).await;
+            let delay = scheduler.next_delay(PollOutcome::of(&update), schedule::jitter());
+            if scheduler.failures() > 0 {
+                warn!(
+                    source = %provider.name(),
+                    failures = scheduler.failures(),
+                    "backing off for {}s",
+                    delay.as_secs()
+                );
+            }
+            let _ = tx.send(update);
+            let _ = tx.send(Event::PollScheduled {
+                source: provider.name().to_string(),
+                schedule: PollSchedule {
+                    interval: delay,
+                    next_poll: Instant::now() + delay,
+                },
+            });
+            tokio::time::sleep(delay).await;
+        }
+    });
+}
+
 /// Puts the terminal into TUI-friendly mode.
 ///
 /// Enables raw mode (no line buffering, key-by-key input), switches to the
@@ -205,6 +290,18 @@ fn restore_terminal(mut terminal: Terminal<CrosstermBackend<io::Stdout>>) -> Res
     Ok(())
 }
 
+/// Rings the terminal bell (BEL) for a newly raised aircraft alert.
+///
+/// # Errors
+///
+/// Returns an error if writing to the terminal fails.
+fn ring_bell(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    let backend = terminal.backend_mut();
+    backend.write_all(b"\x07")?;
+    backend.flush()?;
+    Ok(())
+}
+
 /// Installs a custom panic hook that restores the terminal before panicking.
 ///
 /// For a TUI, a panic would otherwise leave the terminal in raw mode and the
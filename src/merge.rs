@@ -0,0 +1,304 @@
+//! Multi-source flight merging for the Redwood flight tracker.
+//!
+//! When several feeds are configured (e.g. OpenSky plus a local receiver),
+//! each one sends its own [`Event::FlightUpdate`](crate::events::Event::FlightUpdate).
+//! [`FlightMerger`] combines them by ICAO24: for every group of fields
+//! (position, altitude, motion, identity, DB enrichment) it keeps the value
+//! with the most recent last-contact time, and records which source supplied
+//! the position in [`Flight::source`].
+
+use crate::models::Flight;
+use std::collections::{HashMap, HashSet};
+
+/// Unix time (seconds) at which each field group was last taken from a source.
+#[derive(Debug, Clone, Copy, Default)]
+struct FieldTimes {
+    position: i64,
+    altitude: i64,
+    motion: i64,
+    callsign: i64,
+    squawk: i64,
+    category: i64,
+    non_icao: i64,
+    enrichment: i64,
+}
+
+#[derive(Debug, Clone)]
+struct MergedAircraft {
+    flight: Flight,
+    times: FieldTimes,
+    /// Sources whose latest update still contains this aircraft.
+    reporters: HashSet<String>,
+}
+
+/// Merged view of every configured source, keyed by lowercase ICAO24.
+#[derive(Debug, Default)]
+pub struct FlightMerger {
+    aircraft: HashMap<String, MergedAircraft>,
+}
+
+impl FlightMerger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies a successful update from `source`.
+    ///
+    /// Each flight's `last_contact` (or `now`, if the source does not report
+    /// one) decides, field group by field group, whether it replaces what is
+    /// already known. Aircraft that `source` previously reported but no
+    /// longer does are released by it, and dropped once no source reports
+    /// them.
+    pub fn apply(&mut self, source: &str, flights: Vec<Flight>, now: i64) {
+        let mut reported = HashSet::with_capacity(flights.len());
+
+        for mut incoming in flights {
+            let key = incoming.icao24.trim().to_lowercase();
+            let t = incoming.last_contact.unwrap_or(now);
+            incoming.icao24 = key.clone();
+            incoming.source = Some(source.to_string());
+            reported.insert(key.clone());
+
+            match self.aircraft.get_mut(&key) {
+                None => {
+                    incoming.last_contact = Some(t);
+                    self.aircraft.insert(
+                        key,
+                        MergedAircraft {
+                            flight: incoming,
+                            times: FieldTimes {
+                                position: t,
+                                altitude: t,
+                                motion: t,
+                                callsign: t,
+                                squawk: t,
+                                category: t,
+                                non_icao: t,
+                                enrichment: t,
+                            },
+                            reporters: HashSet::from([source.to_string()]),
+                        },
+                    );
+                }
+                Some(existing) => {
+                    existing.reporters.insert(source.to_string());
+                    merge_into(existing, incoming, t);
+                }
+            }
+        }
+
+        self.aircraft.retain(|key, a| {
+            if !reported.contains(key) {
+                a.reporters.remove(source);
+            }
+            !a.reporters.is_empty()
+        });
+    }
+
+    /// Merged state of one aircraft, by lowercase ICAO24.
+    pub fn get(&self, icao24: &str) -> Option<&Flight> {
+        self.aircraft.get(icao24).map(|a| &a.flight)
+    }
+
+    /// Current merged flights, in no particular order.
+    pub fn flights(&self) -> Vec<Flight> {
+        self.aircraft.values().map(|a| a.flight.clone()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.aircraft.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aircraft.is_empty()
+    }
+}
+
+/// Copies every field group of `incoming` (received at `t`) that is at least
+/// as fresh as the stored one.
+fn merge_into(existing: &mut MergedAircraft, incoming: Flight, t: i64) {
+    let flight = &mut existing.flight;
+    let times = &mut existing.times;
+
+    if t >= times.position {
+        flight.latitude = incoming.latitude;
+        flight.longitude = incoming.longitude;
+        flight.time_position = incoming.time_position;
+        flight.position_source = incoming.position_source;
+        flight.sensors = incoming.sensors;
+        flight.source = incoming.source;
+        times.position = t;
+    }
+    if t >= times.altitude {
+        flight.altitude = incoming.altitude;
+        flight.on_ground = incoming.on_ground;
+        if incoming.geo_altitude.is_some() {
+            flight.geo_altitude = incoming.geo_altitude;
+        }
+        times.altitude = t;
+    }
+    if t >= times.motion {
+        flight.velocity = incoming.velocity;
+        flight.true_track = incoming.true_track;
+        flight.vertical_rate = incoming.vertical_rate;
+        times.motion = t;
+    }
+    let has_callsign = !incoming.callsign.is_empty() && incoming.callsign != "N/A";
+    if has_callsign && (t >= times.callsign || flight.callsign == "N/A") {
+        flight.callsign = incoming.callsign;
+        times.callsign = t;
+    }
+    if incoming.squawk.is_some() && (t >= times.squawk || flight.squawk.is_none()) {
+        flight.squawk = incoming.squawk;
+        flight.spi = incoming.spi;
+        times.squawk = t;
+    }
+    if incoming.category.is_some() && (t >= times.category || flight.category.is_none()) {
+        flight.category = incoming.category;
+        times.category = t;
+    }
+    if t >= times.non_icao {
+        flight.non_icao = incoming.non_icao;
+        times.non_icao = t;
+    }
+    if flight.origin_country.is_empty() || flight.origin_country == "Unknown" {
+        flight.origin_country = incoming.origin_country;
+    }
+
+    // DB enrichment is keyed by ICAO24, so a fresher enriched report carries
+    // the DB's current view (e.g. after an import) and replaces it wholesale;
+    // an unenriched one only fills gaps.
+    let enriched = incoming.registration.is_some()
+        || incoming.operator.is_some()
+        || incoming.operator_callsign.is_some()
+        || incoming.manufacturer.is_some()
+        || incoming.model.is_some()
+        || incoming.aircraft_type.is_some();
+    if enriched && t >= times.enrichment {
+        flight.registration = incoming.registration;
+        flight.operator = incoming.operator;
+        flight.operator_callsign = incoming.operator_callsign;
+        flight.manufacturer = incoming.manufacturer;
+        flight.model = incoming.model;
+        flight.aircraft_type = incoming.aircraft_type;
+        times.enrichment = t;
+    } else {
+        flight.registration = flight.registration.take().or(incoming.registration);
+        flight.operator = flight.operator.take().or(incoming.operator);
+        flight.operator_callsign = flight
+            .operator_callsign
+            .take()
+            .or(incoming.operator_callsign);
+        flight.manufacturer = flight.manufacturer.take().or(incoming.manufacturer);
+        flight.model = flight.model.take().or(incoming.model);
+        flight.aircraft_type = flight.aircraft_type.take().or(incoming.aircraft_type);
+    }
+
+    flight.last_contact = flight.last_contact.max(Some(t));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flight(icao: &str, lat: f64, alt: f32, last_contact: i64) -> Flight {
+        Flight {
+            icao24: icao.to_string(),
+            callsign: "N/A".to_string(),
+            latitude: lat,
+            longitude: -122.0,
+            altitude: alt,
+            last_contact: Some(last_contact),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_freshest_fields_win() {
+        let mut merger = FlightMerger::new();
+
+        let mut from_opensky = flight("abc123", 37.0, 1000.0, 100);
+        from_opensky.callsign = "UAL1".to_string();
+        from_opensky.registration = Some("N1".to_string());
+        merger.apply("opensky", vec![from_opensky], 110);
+
+        let mut from_receiver = flight("ABC123", 37.1, 1200.0, 108);
+        from_receiver.squawk = Some("7000".to_string());
+        merger.apply("sbs", vec![from_receiver], 110);
+
+        // An older OpenSky report must not roll the position back.
+        merger.apply("opensky", vec![flight("abc123", 36.9, 900.0, 104)], 111);
+
+        let merged = merger.flights();
+        assert_eq!(merged.len(), 1);
+        let f = &merged[0];
+        assert_eq!(f.latitude, 37.1);
+        assert_eq!(f.altitude, 1200.0);
+        assert_eq!(f.source.as_deref(), Some("sbs"));
+        assert_eq!(f.callsign, "UAL1");
+        assert_eq!(f.squawk.as_deref(), Some("7000"));
+        assert_eq!(f.registration.as_deref(), Some("N1"));
+        assert_eq!(f.last_contact, Some(108));
+    }
+
+    #[test]
+    fn test_category_and_enrichment_follow_freshest_report() {
+        let mut merger = FlightMerger::new();
+        let mut first = flight("abc123", 37.0, 1000.0, 100);
+        first.category = Some(2);
+        first.registration = Some("N1".to_string());
+        first.aircraft_type = Some("C172".to_string());
+        merger.apply("opensky", vec![first], 100);
+
+        // Newer report after a DB import re-registered the airframe.
+        let mut newer = flight("abc123", 37.0, 1000.0, 105);
+        newer.category = Some(4);
+        newer.registration = Some("N2".to_string());
+        merger.apply("sbs", vec![newer], 105);
+
+        // An older report carries neither back in; one without a category
+        // or DB data does not clear them.
+        let mut older = flight("abc123", 37.0, 1000.0, 101);
+        older.category = Some(2);
+        older.registration = Some("N1".to_string());
+        merger.apply("opensky", vec![older], 106);
+        merger.apply("sbs", vec![flight("abc123", 37.0, 1000.0, 110)], 110);
+
+        let f = merger.get("abc123").unwrap();
+        assert_eq!(f.category, Some(4));
+        assert_eq!(f.registration.as_deref(), Some("N2"));
+        // The import dropped the type; it does not linger from before.
+        assert_eq!(f.aircraft_type, None);
+    }
+
+    #[test]
+    fn test_non_icao_flag_follows_freshest_report() {
+        let mut merger = FlightMerger::new();
+        merger.apply("opensky", vec![flight("~2d0001", 37.0, 0.0, 100)], 100);
+        assert!(!merger.get("~2d0001").unwrap().non_icao);
+
+        let tisb = Flight {
+            non_icao: true,
+            ..flight("~2d0001", 37.0, 0.0, 105)
+        };
+        merger.apply("dump1090", vec![tisb], 105);
+        // A stale report without the flag does not clear it.
+        merger.apply("opensky", vec![flight("~2d0001", 37.0, 0.0, 101)], 106);
+        assert!(merger.get("~2d0001").unwrap().non_icao);
+    }
+
+    #[test]
+    fn test_aircraft_dropped_when_no_source_reports_it() {
+        let mut merger = FlightMerger::new();
+        merger.apply("opensky", vec![flight("aaaaaa", 37.0, 0.0, 1)], 1);
+        merger.apply("sbs", vec![flight("aaaaaa", 37.0, 0.0, 1)], 1);
+
+        merger.apply("opensky", vec![], 2);
+        assert_eq!(merger.len(), 1);
+
+        merger.apply("sbs", vec![flight("bbbbbb", 37.0, 0.0, 3)], 3);
+        let merged = merger.flights();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].icao24, "bbbbbb");
+    }
+}
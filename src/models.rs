@@ -4,20 +4,21 @@
 //! - **[`Flight`]** — A single aircraft’s state (position, identity, telemetry), populated from
 //!   the OpenSky API and optionally enriched by the local aircraft database.
 //! - **[`OpenSkyResponse`]** — Raw JSON response shape from the OpenSky “states” API.
-//! - **Conversion** from OpenSky’s state-vector format into [`Flight`] via [`From`].
+//! - **[`StateVector`]** — Fallible, typed parsing of OpenSky’s state-vector
+//!   format via [`TryFrom`], converted into [`Flight`] for rows with a position.
 //! - **[`load_aircraft_csv`]** — Builds a lookup map (ICAO24 → operator/type) from the
 //!   aircraft CSV; the DB layer uses this data when building the SQLite DB.
 
 use csv::ReaderBuilder;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tracing::error;
+use tracing::{error, warn};
 
 /// A single aircraft’s current state and identity.
 ///
 /// Core fields come from the [OpenSky state vector](https://opensky-network.org/docs/api/v1.html#response)
 /// (e.g. position, altitude, velocity). Optional fields are filled when the
-/// aircraft is found in the local aircraft database (see `db::decorate_flights`).
+/// aircraft is found in the local aircraft database (see `db::AircraftRegistry`).
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Flight {
     /// Flight or operator callsign (e.g. "UAL123")
@@ -54,58 +55,380 @@ pub struct Flight {
     pub model: Option<String>,
     /// Registration (e.g. "N12345").
     pub registration: Option<String>,
+    /// Transponder squawk code (e.g. "7000").
+    pub squawk: Option<String>,
+    /// Unix timestamp (seconds) of the last message received from this aircraft.
+    pub last_contact: Option<i64>,
+    /// Unix timestamp (seconds) of the last position update.
+    pub time_position: Option<i64>,
+    /// Whether the aircraft reports being on the ground (surface position).
+    pub on_ground: bool,
+    /// Geometric (GNSS) altitude in meters, when reported.
+    pub geo_altitude: Option<f32>,
+    /// Special position identification ("ident") flag set by the pilot.
+    pub spi: bool,
+    /// How the current position was obtained.
+    pub position_source: Option<PositionSource>,
+    /// IDs of the OpenSky receivers that contributed to this state vector.
+    pub sensors: Option<Vec<i64>>,
+    /// Emitter category code (OpenSky numbering, see [`category_label`]).
+    pub category: Option<u8>,
+    /// Name of the flight source that supplied the current position.
+    pub source: Option<String>,
+    /// Whether the position is a dead-reckoning estimate rather than the
+    /// last reported one (see [`Flight::project`]).
+    #[serde(default)]
+    pub extrapolated: bool,
+    /// Whether `icao24` is a non-ICAO address (readsb's `~` prefix, e.g.
+    /// TIS-B targets) that has no address block or registry entry.
+    #[serde(default)]
+    pub non_icao: bool,
+}
+
+/// Origin of an aircraft's position (OpenSky `position_source`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PositionSource {
+    /// Broadcast by the aircraft itself.
+    AdsB,
+    /// Radar data via ASTERIX.
+    Asterix,
+    /// Multilateration from several receivers.
+    Mlat,
+    /// FLARM (gliders and light aircraft).
+    Flarm,
+}
+
+impl PositionSource {
+    /// Maps OpenSky's numeric code (0–3).
+    pub fn from_code(code: u64) -> Option<Self> {
+        match code {
+            0 => Some(Self::AdsB),
+            1 => Some(Self::Asterix),
+            2 => Some(Self::Mlat),
+            3 => Some(Self::Flarm),
+            _ => None,
+        }
+    }
+
+    /// Short label for display.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::AdsB => "ADS-B",
+            Self::Asterix => "ASTERIX",
+            Self::Mlat => "MLAT",
+            Self::Flarm => "FLARM",
+        }
+    }
+}
+
+/// Describes an OpenSky aircraft category code (the `category` field of
+/// extended state vectors). Returns `None` for codes that carry no
+/// information (0, 1) and for unknown codes.
+pub fn category_label(code: u8) -> Option<&'static str> {
+    Some(match code {
+        2 => "Light (< 15500 lbs)",
+        3 => "Small (15500-75000 lbs)",
+        4 => "Large (75000-300000 lbs)",
+        5 => "High Vortex Large",
+        6 => "Heavy (> 300000 lbs)",
+        7 => "High Performance",
+        8 => "Rotorcraft",
+        9 => "Glider / Sailplane",
+        10 => "Lighter-than-air",
+        11 => "Parachutist / Skydiver",
+        12 => "Ultralight / Hang-glider",
+        14 => "Unmanned Aerial Vehicle",
+        15 => "Space Vehicle",
+        16 => "Emergency Vehicle",
+        17 => "Service Vehicle",
+        18 => "Point Obstacle",
+        19 => "Cluster Obstacle",
+        20 => "Line Obstacle",
+        _ => return None,
+    })
 }
 
 /// Raw response from the OpenSky Network “states/all” (or bounding-box) API.
 ///
 /// `states` is an optional array of state vectors. Each vector is an array of
 /// [`serde_json::Value`]s whose indices follow the [OpenSky state vector format](https://opensky-network.org/docs/api/v1.html#response).
+/// Use [`OpenSkyResponse::into_flights`] to parse them.
 #[derive(Deserialize)]
 pub struct OpenSkyResponse {
     pub states: Option<Vec<Vec<serde_json::Value>>>,
 }
 
-/// Builds a [`Flight`] from a single OpenSky state vector.
-///
-/// Indices follow the [OpenSky API state vector](https://opensky-network.org/docs/api/v1.html#response):
-/// 0 = icao24, 1 = callsign, 2 = origin_country, 5 = longitude, 6 = latitude,
-/// 7 = altitude, 9 = velocity, 10 = true_track, 11 = vertical_rate. Fields not
-/// provided by the API (operator, registration, etc.) are set to `None` and
-/// can be filled later by `db::decorate_flights`.
-///
-/// # Arguments
-///
-/// * `data` - A vector of [`serde_json::Value`]s representing the OpenSky state vector.
+impl OpenSkyResponse {
+    /// Parses every state vector into a [`Flight`].
+    ///
+    /// Malformed rows (see [`StateVectorError`]) are skipped and counted in a
+    /// single warning; rows without a position are dropped silently, since
+    /// they cannot be placed on the map.
+    pub fn into_flights(self) -> Vec<Flight> {
+        let rows = self.states.unwrap_or_default();
+        let mut flights = Vec::with_capacity(rows.len());
+        let mut skipped = 0;
+        let mut first_error = None;
+
+        for row in rows {
+            match StateVector::try_from(row) {
+                Ok(state) => flights.extend(state.into_flight()),
+                Err(e) => {
+                    skipped += 1;
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        if let Some(e) = first_error {
+            warn!(skipped, first_error = %e, "skipped malformed OpenSky state vectors");
+        }
+        flights
+    }
+}
+
+/// Why an OpenSky state vector could not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateVectorError {
+    /// Fewer than the 17 fields every state vector carries.
+    TooShort { len: usize },
+    /// A field is present but has the wrong JSON type.
+    WrongType {
+        field: &'static str,
+        expected: &'static str,
+    },
+    /// A required field is `null` or empty.
+    Missing { field: &'static str },
+    /// A numeric field is outside its valid range.
+    OutOfRange { field: &'static str, value: f64 },
+}
+
+impl std::fmt::Display for StateVectorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooShort { len } => write!(f, "state vector has {} fields, expected 17", len),
+            Self::WrongType { field, expected } => write!(f, "{} is not {}", field, expected),
+            Self::Missing { field } => write!(f, "{} is missing", field),
+            Self::OutOfRange { field, value } => write!(f, "{} out of range: {}", field, value),
+        }
+    }
+}
+
+impl std::error::Error for StateVectorError {}
+
+/// One typed OpenSky state vector.
 ///
-/// # Returns
+/// Every field the API may send as `null` is an [`Option`]; in particular a
+/// missing position stays `None` rather than defaulting to 0°/0°.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateVector {
+    pub icao24: String,
+    pub callsign: Option<String>,
+    pub origin_country: Option<String>,
+    pub time_position: Option<i64>,
+    pub last_contact: Option<i64>,
+    pub longitude: Option<f64>,
+    pub latitude: Option<f64>,
+    /// Barometric altitude in meters.
+    pub baro_altitude: Option<f64>,
+    pub on_ground: bool,
+    /// Ground speed in meters per second.
+    pub velocity: Option<f64>,
+    pub true_track: Option<f64>,
+    /// Vertical rate in meters per second.
+    pub vertical_rate: Option<f64>,
+    pub sensors: Option<Vec<i64>>,
+    /// Geometric altitude in meters.
+    pub geo_altitude: Option<f64>,
+    pub squawk: Option<String>,
+    pub spi: bool,
+    pub position_source: Option<PositionSource>,
+    pub category: Option<u8>,
+}
+
+/// Typed accessors for the fields of one raw state vector.
+struct Fields<'a>(&'a [serde_json::Value]);
+
+impl Fields<'_> {
+    /// The value at `index`, with `null` and absent trailing fields as `None`.
+    fn get(&self, index: usize) -> Option<&serde_json::Value> {
+        self.0.get(index).filter(|v| !v.is_null())
+    }
+
+    fn str(&self, index: usize, field: &'static str) -> Result<Option<String>, StateVectorError> {
+        match self.get(index) {
+            None => Ok(None),
+            Some(v) => v
+                .as_str()
+                .map(|s| Some(s.trim().to_string()).filter(|s| !s.is_empty()))
+                .ok_or(StateVectorError::WrongType {
+                    field,
+                    expected: "a string",
+                }),
+        }
+    }
+
+    fn f64(&self, index: usize, field: &'static str) -> Result<Option<f64>, StateVectorError> {
+        match self.get(index) {
+            None => Ok(None),
+            Some(v) => v.as_f64().map(Some).ok_or(StateVectorError::WrongType {
+                field,
+                expected: "a number",
+            }),
+        }
+    }
+
+    /// Unix timestamps are integers, but fractional values are tolerated.
+    fn time(&self, index: usize, field: &'static str) -> Result<Option<i64>, StateVectorError> {
+        match self.get(index) {
+            None => Ok(None),
+            Some(v) => v
+                .as_i64()
+                .or_else(|| v.as_f64().filter(|t| t.abs() < 1e15).map(|t| t as i64))
+                .map(Some)
+                .ok_or(StateVectorError::WrongType {
+                    field,
+                    expected: "a timestamp",
+                }),
+        }
+    }
+
+    fn bool(&self, index: usize, field: &'static str) -> Result<bool, StateVectorError> {
+        match self.get(index) {
+            None => Ok(false),
+            Some(v) => v.as_bool().ok_or(StateVectorError::WrongType {
+                field,
+                expected: "a boolean",
+            }),
+        }
+    }
+
+    /// Small enumerated codes; unknown values are `None` for forward
+    /// compatibility, non-integers are errors.
+    fn code(&self, index: usize, field: &'static str) -> Result<Option<u64>, StateVectorError> {
+        match self.get(index) {
+            None => Ok(None),
+            Some(v) => v.as_u64().map(Some).ok_or(StateVectorError::WrongType {
+                field,
+                expected: "an integer code",
+            }),
+        }
+    }
+
+    fn sensors(&self, index: usize) -> Result<Option<Vec<i64>>, StateVectorError> {
+        let wrong = StateVectorError::WrongType {
+            field: "sensors",
+            expected: "an array of integers",
+        };
+        match self.get(index) {
+            None => Ok(None),
+            Some(v) => v
+                .as_array()
+                .ok_or_else(|| wrong.clone())?
+                .iter()
+                .map(|id| id.as_i64().ok_or_else(|| wrong.clone()))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Some),
+        }
+    }
+}
+
+/// Rejects values outside `range`.
+fn in_range(
+    value: Option<f64>,
+    range: std::ops::RangeInclusive<f64>,
+    field: &'static str,
+) -> Result<Option<f64>, StateVectorError> {
+    match value {
+        Some(v) if !range.contains(&v) => Err(StateVectorError::OutOfRange { field, value: v }),
+        other => Ok(other),
+    }
+}
+
+/// Parses a single OpenSky state vector.
 ///
-/// A [`Flight`] struct populated with the data from the OpenSky state vector.
+/// Indices follow the [OpenSky API state vector](https://opensky-network.org/docs/api/v1.html#response):
+/// 0 = icao24, 1 = callsign, 2 = origin_country, 3 = time_position,
+/// 4 = last_contact, 5 = longitude, 6 = latitude, 7 = baro_altitude,
+/// 8 = on_ground, 9 = velocity, 10 = true_track, 11 = vertical_rate,
+/// 12 = sensors, 13 = geo_altitude, 14 = squawk, 15 = spi,
+/// 16 = position_source and, when requested with `extended=1`, 17 = category.
 ///
-/// # Panics
+/// # Errors
 ///
-/// Does not panic; missing or invalid values use defaults (e.g. 0.0 for numbers,
-/// "N/A" or "Unknown" for strings).
-impl From<Vec<serde_json::Value>> for Flight {
-    fn from(data: Vec<serde_json::Value>) -> Self {
-        Self {
-            callsign: data[1].as_str().unwrap_or("N/A").trim().to_string(),
-            origin_country: data[2].as_str().unwrap_or("Unknown").to_string(),
-            longitude: data[5].as_f64().unwrap_or(0.0),
-            latitude: data[6].as_f64().unwrap_or(0.0),
-            altitude: data[7].as_f64().unwrap_or(0.0) as f32,
-            velocity: data[9].as_f64().unwrap_or(0.0) as f32,
-            true_track: data[10].as_f64().unwrap_or(0.0) as f32,
-            origin_airport: None,
-            destination_airport: None,
-            operator: None,
-            icao24: data[0].as_str().unwrap_or("N/A").trim().to_string(),
-            vertical_rate: data[11].as_f64().unwrap_or(0.0),
-            operator_callsign: None,
-            manufacturer: None,
-            model: None,
-            registration: None,
-            aircraft_type: None,
+/// Returns a [`StateVectorError`] if the vector is shorter than 17 fields,
+/// has no ICAO24 address, holds a value of the wrong JSON type, or has a
+/// latitude/longitude outside the valid range.
+impl TryFrom<Vec<serde_json::Value>> for StateVector {
+    type Error = StateVectorError;
+
+    fn try_from(data: Vec<serde_json::Value>) -> Result<Self, Self::Error> {
+        if data.len() < 17 {
+            return Err(StateVectorError::TooShort { len: data.len() });
         }
+        let fields = Fields(&data);
+
+        let icao24 = fields
+            .str(0, "icao24")?
+            .ok_or(StateVectorError::Missing { field: "icao24" })?
+            .to_lowercase();
+
+        Ok(Self {
+            icao24,
+            callsign: fields.str(1, "callsign")?,
+            origin_country: fields.str(2, "origin_country")?,
+            time_position: fields.time(3, "time_position")?,
+            last_contact: fields.time(4, "last_contact")?,
+            longitude: in_range(fields.f64(5, "longitude")?, -180.0..=180.0, "longitude")?,
+            latitude: in_range(fields.f64(6, "latitude")?, -90.0..=90.0, "latitude")?,
+            baro_altitude: fields.f64(7, "baro_altitude")?,
+            on_ground: fields.bool(8, "on_ground")?,
+            velocity: fields.f64(9, "velocity")?,
+            true_track: fields.f64(10, "true_track")?,
+            vertical_rate: fields.f64(11, "vertical_rate")?,
+            sensors: fields.sensors(12)?,
+            geo_altitude: fields.f64(13, "geo_altitude")?,
+            squawk: fields.str(14, "squawk")?,
+            spi: fields.bool(15, "spi")?,
+            position_source: fields
+                .code(16, "position_source")?
+                .and_then(PositionSource::from_code),
+            category: fields
+                .code(17, "category")?
+                .and_then(|c| u8::try_from(c).ok()),
+        })
+    }
+}
+
+impl StateVector {
+    /// Converts to a [`Flight`] (metric units, as sent by OpenSky).
+    ///
+    /// Returns `None` when the vector has no position. Fields not provided
+    /// by the API (operator, registration, etc.) are left `None` for
+    /// `db::AircraftRegistry` to fill.
+    pub fn into_flight(self) -> Option<Flight> {
+        let (latitude, longitude) = (self.latitude?, self.longitude?);
+        Some(Flight {
+            callsign: self.callsign.unwrap_or_else(|| "N/A".to_string()),
+            origin_country: self.origin_country.unwrap_or_else(|| "Unknown".to_string()),
+            longitude,
+            latitude,
+            altitude: self.baro_altitude.or(self.geo_altitude).unwrap_or(0.0) as f32,
+            velocity: self.velocity.unwrap_or(0.0) as f32,
+            true_track: self.true_track.unwrap_or(0.0) as f32,
+            icao24: self.icao24,
+            vertical_rate: self.vertical_rate.unwrap_or(0.0),
+            squawk: self.squawk,
+            last_contact: self.last_contact,
+            time_position: self.time_position,
+            on_ground: self.on_ground,
+            geo_altitude: self.geo_altitude.map(|a| a as f32),
+            spi: self.spi,
+            position_source: self.position_source,
+            sensors: self.sensors,
+            category: self.category,
+            ..Default::default()
+        })
     }
 }
 
@@ -141,6 +464,175 @@ impl Flight {
 
         r * c
     }
+
+    /// Human-readable emitter category, if known.
+    pub fn category_label(&self) -> Option<&'static str> {
+        self.category.and_then(category_label)
+    }
+
+    /// Look angles from `observer`, using the geometric altitude when
+    /// reported and the barometric one otherwise.
+    pub fn look_angles(&self, observer: &Observer) -> LookAngles {
+        let altitude = self.geo_altitude.unwrap_or(self.altitude);
+        observer.look_at(self.latitude, self.longitude, altitude as f64)
+    }
+
+    /// Closest point of approach to (`lat`, `lon`) at the current ground
+    /// speed and track.
+    ///
+    /// Works in a flat east/north plane centred on the observer (longitude
+    /// scaled by cos(lat)), which is accurate to well under 1% over the tens
+    /// of kilometres an aircraft covers in a few minutes.
+    pub fn closest_approach(&self, lat: f64, lon: f64) -> ClosestApproach {
+        let km_per_deg = 6371.0_f64.to_radians();
+        let d_lon = (self.longitude - lon + 540.0).rem_euclid(360.0) - 180.0;
+        let east = d_lon * km_per_deg * lat.to_radians().cos();
+        let north = (self.latitude - lat) * km_per_deg;
+
+        let speed_kms = self.velocity as f64 / 1000.0;
+        let (sin_t, cos_t) = (self.true_track as f64).to_radians().sin_cos();
+        let (v_east, v_north) = (speed_kms * sin_t, speed_kms * cos_t);
+
+        let v2 = v_east * v_east + v_north * v_north;
+        let time_secs = if v2 > 0.0 {
+            (-(east * v_east + north * v_north) / v2).max(0.0)
+        } else {
+            0.0
+        };
+        let cpa_east = east + v_east * time_secs;
+        let cpa_north = north + v_north * time_secs;
+        ClosestApproach {
+            distance_km: cpa_east.hypot(cpa_north),
+            time_secs,
+            bearing_deg: cpa_east.atan2(cpa_north).to_degrees().rem_euclid(360.0),
+        }
+    }
+
+    /// Dead-reckons this flight `elapsed_secs` ahead of its reported state.
+    ///
+    /// Follows the great circle leaving the reported position on
+    /// `true_track` at `velocity`, and climbs or descends at
+    /// `vertical_rate` (never below 0 m).
+    ///
+    /// # Returns
+    ///
+    /// `(latitude, longitude, altitude)` in decimal degrees and meters.
+    pub fn project(&self, elapsed_secs: f64) -> (f64, f64, f32) {
+        let distance_km = self.velocity as f64 * elapsed_secs / 1000.0;
+        let (lat, lon) = destination_point(
+            self.latitude,
+            self.longitude,
+            self.true_track as f64,
+            distance_km,
+        );
+        let altitude = (self.altitude as f64 + self.vertical_rate * elapsed_secs).max(0.0);
+        (lat, lon, altitude as f32)
+    }
+}
+
+/// WGS-84 semi-major axis in meters.
+const WGS84_A: f64 = 6_378_137.0;
+/// WGS-84 first eccentricity squared.
+const WGS84_E2: f64 = 6.694_379_990_14e-3;
+
+/// A ground observer: position plus height above the WGS-84 ellipsoid.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Observer {
+    /// Latitude in decimal degrees.
+    pub latitude: f64,
+    /// Longitude in decimal degrees.
+    pub longitude: f64,
+    /// Height in meters.
+    pub altitude_m: f64,
+}
+
+/// Where to look for an aircraft from an [`Observer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LookAngles {
+    /// Compass bearing in degrees, clockwise from true north (0–360).
+    pub azimuth_deg: f64,
+    /// Angle above the local horizon in degrees; negative when below it.
+    pub elevation_deg: f64,
+    /// Straight-line (3-D) distance in kilometres.
+    pub slant_range_km: f64,
+}
+
+impl Observer {
+    /// Azimuth, elevation and slant range to a point at `altitude_m`.
+    ///
+    /// Both positions are converted to Earth-centred, Earth-fixed (ECEF)
+    /// coordinates on the WGS-84 ellipsoid; the difference is rotated into
+    /// the observer's local east-north-up (ENU) frame.
+    pub fn look_at(&self, lat: f64, lon: f64, altitude_m: f64) -> LookAngles {
+        let o = geodetic_to_ecef(self.latitude, self.longitude, self.altitude_m);
+        let t = geodetic_to_ecef(lat, lon, altitude_m);
+        let (dx, dy, dz) = (t[0] - o[0], t[1] - o[1], t[2] - o[2]);
+
+        let (sin_phi, cos_phi) = self.latitude.to_radians().sin_cos();
+        let (sin_lam, cos_lam) = self.longitude.to_radians().sin_cos();
+        let east = -sin_lam * dx + cos_lam * dy;
+        let north = -sin_phi * cos_lam * dx - sin_phi * sin_lam * dy + cos_phi * dz;
+        let up = cos_phi * cos_lam * dx + cos_phi * sin_lam * dy + sin_phi * dz;
+
+        let range = (dx * dx + dy * dy + dz * dz).sqrt();
+        let elevation = if range > 0.0 {
+            (up / range).clamp(-1.0, 1.0).asin().to_degrees()
+        } else {
+            90.0
+        };
+        LookAngles {
+            azimuth_deg: east.atan2(north).to_degrees().rem_euclid(360.0),
+            elevation_deg: elevation,
+            slant_range_km: range / 1000.0,
+        }
+    }
+}
+
+/// Predicted closest point of approach (CPA) of an aircraft to a point on
+/// the ground, assuming it holds its current ground speed and track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClosestApproach {
+    /// Ground distance at closest approach in kilometres.
+    pub distance_km: f64,
+    /// Seconds until closest approach; 0 when the aircraft is moving away
+    /// (or not moving), in which case it is closest now.
+    pub time_secs: f64,
+    /// Bearing of the closest-approach point from the observer, degrees
+    /// clockwise from north.
+    pub bearing_deg: f64,
+}
+
+/// Converts geodetic coordinates to ECEF `[x, y, z]` in meters.
+fn geodetic_to_ecef(lat: f64, lon: f64, altitude_m: f64) -> [f64; 3] {
+    let (sin_phi, cos_phi) = lat.to_radians().sin_cos();
+    let (sin_lam, cos_lam) = lon.to_radians().sin_cos();
+    let n = WGS84_A / (1.0 - WGS84_E2 * sin_phi * sin_phi).sqrt();
+    [
+        (n + altitude_m) * cos_phi * cos_lam,
+        (n + altitude_m) * cos_phi * sin_lam,
+        (n * (1.0 - WGS84_E2) + altitude_m) * sin_phi,
+    ]
+}
+
+/// Point reached by travelling `distance_km` along a great circle from
+/// (`lat`, `lon`) with initial bearing `bearing_deg` (clockwise from north).
+///
+/// Uses the spherical [direct formula](https://www.movable-type.co.uk/scripts/latlong.html#dest-point)
+/// with Earth's radius 6371 km, matching [`Flight::distance_from`]. The
+/// returned longitude is normalised to `[-180, 180)`.
+pub fn destination_point(lat: f64, lon: f64, bearing_deg: f64, distance_km: f64) -> (f64, f64) {
+    let r = 6371.0; // Earth's radius in km
+    let delta = distance_km / r;
+    let theta = bearing_deg.to_radians();
+    let phi1 = lat.to_radians();
+    let lambda1 = lon.to_radians();
+
+    let phi2 = (phi1.sin() * delta.cos() + phi1.cos() * delta.sin() * theta.cos()).asin();
+    let lambda2 = lambda1
+        + (theta.sin() * delta.sin() * phi1.cos()).atan2(delta.cos() - phi1.sin() * phi2.sin());
+
+    let lon2 = (lambda2.to_degrees() + 540.0).rem_euclid(360.0) - 180.0;
+    (phi2.to_degrees(), lon2)
 }
 
 /// Loads the aircraft CSV into a map keyed by ICAO24.
@@ -252,4 +744,270 @@ mod tests {
             dist_oak
         );
     }
+
+    #[test]
+    fn test_destination_point() {
+        // One degree of arc along the equator.
+        let (lat, lon) = destination_point(0.0, 0.0, 90.0, 111.195);
+        assert!(lat.abs() < 1e-9);
+        assert!((lon - 1.0).abs() < 1e-4);
+
+        // Movable Type reference: 124.8 km from 53°19′14″N 001°43′47″W on
+        // bearing 096°01′18″ ends at 53°11′18″N 000°08′00″E.
+        let (lat, lon) = destination_point(53.3206, -1.7297, 96.0217, 124.8);
+        assert!((lat - 53.1883).abs() < 1e-3, "lat {}", lat);
+        assert!((lon - 0.1333).abs() < 1e-3, "lon {}", lon);
+
+        // Due north over the pole comes back down the other side.
+        let (lat, lon) = destination_point(89.0, 0.0, 0.0, 222.39);
+        assert!((lat - 89.0).abs() < 1e-3);
+        assert!((lon.abs() - 180.0).abs() < 1e-6);
+
+        // Crossing the antimeridian wraps the longitude.
+        let (_, lon) = destination_point(0.0, 179.9, 90.0, 22.239);
+        assert!((lon + 179.9).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_look_angles() {
+        let origin = Observer::default();
+
+        // Straight up.
+        let overhead = origin.look_at(0.0, 0.0, 10_000.0);
+        assert!((overhead.elevation_deg - 90.0).abs() < 1e-6);
+        assert!((overhead.slant_range_km - 10.0).abs() < 1e-6);
+
+        // One degree north at 10 km: the Earth's curvature eats ~1 km of it.
+        let north = origin.look_at(1.0, 0.0, 10_000.0);
+        assert!(north.azimuth_deg.abs() < 1e-9);
+        assert!((north.elevation_deg - 4.6634).abs() < 1e-3);
+        assert!((north.slant_range_km - 111.111).abs() < 1e-2);
+
+        // A point on the ground one degree east is below the horizon.
+        let east = origin.look_at(0.0, 1.0, 0.0);
+        assert!((east.azimuth_deg - 90.0).abs() < 1e-9);
+        assert!((east.elevation_deg + 0.5).abs() < 1e-3);
+
+        // Approach into Heathrow, seen from the airfield.
+        let lhr = Observer {
+            latitude: 51.47,
+            longitude: -0.45,
+            altitude_m: 25.0,
+        };
+        let look = lhr.look_at(51.5, -0.3, 1500.0);
+        assert!((look.azimuth_deg - 72.179).abs() < 1e-2);
+        assert!((look.elevation_deg - 7.628).abs() < 1e-2);
+        assert!((look.slant_range_km - 11.041).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_closest_approach() {
+        // 10 km west of the observer, 1 km north of it, flying due east at
+        // 200 m/s: passes 1 km north in 50 s.
+        let km = 1.0 / 6371.0_f64.to_radians();
+        let flight = Flight {
+            latitude: 1.0 * km,
+            longitude: -10.0 * km,
+            velocity: 200.0,
+            true_track: 90.0,
+            ..Default::default()
+        };
+        let cpa = flight.closest_approach(0.0, 0.0);
+        assert!((cpa.distance_km - 1.0).abs() < 1e-6);
+        assert!((cpa.time_secs - 50.0).abs() < 1e-6);
+        assert!(cpa.bearing_deg.abs() < 1e-6);
+
+        // Flying away: closest now.
+        let receding = Flight {
+            true_track: 270.0,
+            ..flight.clone()
+        };
+        let cpa = receding.closest_approach(0.0, 0.0);
+        assert_eq!(cpa.time_secs, 0.0);
+        assert!((cpa.distance_km - 101.0_f64.sqrt()).abs() < 1e-6);
+
+        // Stationary aircraft.
+        let parked = Flight {
+            velocity: 0.0,
+            ..flight
+        };
+        assert_eq!(parked.closest_approach(0.0, 0.0).time_secs, 0.0);
+    }
+
+    #[test]
+    fn test_project_flight() {
+        let flight = Flight {
+            latitude: 0.0,
+            longitude: 0.0,
+            altitude: 1000.0,
+            velocity: 250.0,
+            true_track: 0.0,
+            vertical_rate: -5.0,
+            ..Default::default()
+        };
+        // 60 s at 250 m/s = 15 km due north.
+        let (lat, lon, alt) = flight.project(60.0);
+        assert!(
+            (Flight {
+                latitude: lat,
+                longitude: lon,
+                ..Default::default()
+            }
+            .distance_from(0.0, 0.0)
+                - 15.0)
+                .abs()
+                < 1e-6
+        );
+        assert!(lat > 0.0 && lon.abs() < 1e-12);
+        assert_eq!(alt, 700.0);
+
+        // Descending aircraft never go underground.
+        assert_eq!(flight.project(600.0).2, 0.0);
+    }
+
+    #[test]
+    fn test_full_state_vector() {
+        let row: Vec<serde_json::Value> = serde_json::from_str(
+            r#"["a1b2c3","DAL42   ","United States",1700000010,1700000012,
+                -122.38,37.62,0.0,true,6.2,281.0,null,[1234,5678],12.5,
+                "7700",true,2,3]"#,
+        )
+        .unwrap();
+        let f = StateVector::try_from(row).unwrap().into_flight().unwrap();
+        assert_eq!(f.callsign, "DAL42");
+        assert_eq!(f.time_position, Some(1700000010));
+        assert_eq!(f.last_contact, Some(1700000012));
+        assert!(f.on_ground);
+        assert_eq!(f.sensors, Some(vec![1234, 5678]));
+        assert_eq!(f.geo_altitude, Some(12.5));
+        assert_eq!(f.squawk.as_deref(), Some("7700"));
+        assert!(f.spi);
+        assert_eq!(f.position_source, Some(PositionSource::Mlat));
+        assert_eq!(f.category_label(), Some("Small (15500-75000 lbs)"));
+
+        // Non-extended vectors stop at position_source; nulls become None.
+        let short: Vec<serde_json::Value> = serde_json::from_str(
+            r#"["A1B2C3",null,"United States",null,1700000012,null,null,
+                null,false,null,null,null,null,null,null,false,0]"#,
+        )
+        .unwrap();
+        let state = StateVector::try_from(short).unwrap();
+        assert_eq!(state.icao24, "a1b2c3");
+        assert_eq!(state.category, None);
+        assert_eq!(state.squawk, None);
+        assert_eq!(state.sensors, None);
+        assert_eq!(state.position_source, Some(PositionSource::AdsB));
+        // No position: no Null Island.
+        assert_eq!(state.latitude, None);
+        assert!(state.into_flight().is_none());
+    }
+
+    #[test]
+    fn test_malformed_state_vectors() {
+        let parse = |json: &str| {
+            StateVector::try_from(serde_json::from_str::<Vec<serde_json::Value>>(json).unwrap())
+        };
+        assert_eq!(
+            parse(r#"["a1b2c3","DAL42"]"#),
+            Err(StateVectorError::TooShort { len: 2 })
+        );
+        assert_eq!(
+            parse(
+                r#"[null,null,null,null,null,null,null,null,null,null,null,null,null,null,null,null,null]"#
+            ),
+            Err(StateVectorError::Missing { field: "icao24" })
+        );
+        assert_eq!(
+            parse(
+                r#"["a1b2c3",null,null,null,null,"west",1.0,null,null,null,null,null,null,null,null,null,null]"#
+            ),
+            Err(StateVectorError::WrongType {
+                field: "longitude",
+                expected: "a number"
+            })
+        );
+        assert_eq!(
+            parse(
+                r#"["a1b2c3",null,null,null,null,1.0,91.5,null,null,null,null,null,null,null,null,null,null]"#
+            ),
+            Err(StateVectorError::OutOfRange {
+                field: "latitude",
+                value: 91.5
+            })
+        );
+    }
+
+    #[test]
+    fn test_response_skips_bad_rows() {
+        let response: OpenSkyResponse = serde_json::from_str(
+            r#"{"time":0,"states":[
+                ["a1b2c3","DAL42","United States",0,0,-122.3,37.6,1000.0,false,200.0,90.0,0.0,null,null,null,false,0],
+                ["bad"],
+                ["d4e5f6","UAL1","United States",0,0,null,null,1000.0,false,200.0,90.0,0.0,null,null,null,false,0]
+            ]}"#,
+        )
+        .unwrap();
+        let flights = response.into_flights();
+        assert_eq!(flights.len(), 1);
+        assert_eq!(flights[0].icao24, "a1b2c3");
+    }
+
+    mod fuzz {
+        use super::*;
+        use proptest::prelude::*;
+        use serde_json::Value;
+
+        /// Arbitrary JSON, nested a few levels deep.
+        fn json() -> impl Strategy<Value = Value> {
+            let leaf = prop_oneof![
+                Just(Value::Null),
+                any::<bool>().prop_map(Value::Bool),
+                any::<i64>().prop_map(Value::from),
+                any::<f64>().prop_map(Value::from),
+                ".{0,12}".prop_map(Value::String),
+            ];
+            leaf.prop_recursive(3, 16, 4, |inner| {
+                prop_oneof![
+                    prop::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+                    prop::collection::hash_map(".{0,4}", inner, 0..4)
+                        .prop_map(|m| Value::Object(m.into_iter().collect())),
+                ]
+            })
+        }
+
+        /// Rows shaped like real state vectors, with some fields replaced by
+        /// arbitrary JSON.
+        fn near_valid_row() -> impl Strategy<Value = Vec<Value>> {
+            let base: Vec<Value> = serde_json::from_str(
+                r#"["a1b2c3","DAL42","United States",1700000000,1700000000,
+                    -122.3,37.6,1000.0,false,200.0,90.0,0.0,[1],1010.0,"1200",false,0,4]"#,
+            )
+            .unwrap();
+            prop::collection::vec((0..18usize, json()), 0..4).prop_map(move |edits| {
+                let mut row = base.clone();
+                for (i, v) in edits {
+                    row[i] = v;
+                }
+                row
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn arbitrary_rows_never_panic(row in prop::collection::vec(json(), 0..24)) {
+                let _ = StateVector::try_from(row).map(StateVector::into_flight);
+            }
+
+            #[test]
+            fn parsed_positions_are_valid(row in near_valid_row()) {
+                if let Ok(state) = StateVector::try_from(row) {
+                    if let Some(flight) = state.into_flight() {
+                        prop_assert!((-90.0..=90.0).contains(&flight.latitude));
+                        prop_assert!((-180.0..=180.0).contains(&flight.longitude));
+                        prop_assert!(!flight.icao24.is_empty());
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,400 @@
+//! Flight phase inference for the Redwood flight tracker.
+//!
+//! [`FlightPhase::classify`] maps one aircraft state (ground flag, height
+//! above the field, vertical rate, speed) to a phase. Vertical rate is taken
+//! from the track history where possible, since single reports are noisy.
+//! [`PhaseTracker`] adds hysteresis so the phase does not flicker:
+//! thresholds are looser for staying in the current phase than for entering
+//! it, and a new phase must hold for [`MIN_DWELL_SECS`] before it is shown.
+
+use crate::models::Flight;
+use crate::tracks::Track;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Seconds a new phase must persist before it replaces the shown one.
+pub const MIN_DWELL_SECS: i64 = 20;
+
+/// Ground speed (m/s) above which an aircraft on the ground is on its
+/// takeoff or landing roll rather than taxiing (~50 kt).
+const ROLL_SPEED: f64 = 25.0;
+/// Below this height (m) above the field a climb or descent is a takeoff or
+/// landing.
+const RUNWAY_HEIGHT: f64 = 300.0;
+/// Below this height (m) above the field a descent is an approach.
+const APPROACH_HEIGHT: f64 = 1500.0;
+/// Above this height (m) above the field level flight is cruise.
+const CRUISE_HEIGHT: f64 = 6000.0;
+/// Vertical rate (m/s, ~500 ft/min) needed to enter a climb or descent.
+const ENTER_VERTICAL_RATE: f64 = 2.5;
+/// Vertical rate (m/s, ~200 ft/min) below which a climb or descent ends.
+const EXIT_VERTICAL_RATE: f64 = 1.0;
+/// Track history span (s) used to smooth the vertical rate.
+const SMOOTHING_SECS: i64 = 60;
+
+/// Phase of flight.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum FlightPhase {
+    Taxi,
+    Takeoff,
+    Climb,
+    Cruise,
+    Descent,
+    Approach,
+    Landing,
+}
+
+impl FlightPhase {
+    /// Name shown in the UI.
+    pub fn label(self) -> &'static str {
+        match self {
+            FlightPhase::Taxi => "Taxi",
+            FlightPhase::Takeoff => "Takeoff",
+            FlightPhase::Climb => "Climb",
+            FlightPhase::Cruise => "Cruise",
+            FlightPhase::Descent => "Descent",
+            FlightPhase::Approach => "Approach",
+            FlightPhase::Landing => "Landing",
+        }
+    }
+
+    /// One-character icon for lists.
+    pub fn icon(self) -> &'static str {
+        match self {
+            FlightPhase::Taxi => "•",
+            FlightPhase::Takeoff => "⬈",
+            FlightPhase::Climb => "↗",
+            FlightPhase::Cruise => "→",
+            FlightPhase::Descent => "↘",
+            FlightPhase::Approach => "⬊",
+            FlightPhase::Landing => "⊥",
+        }
+    }
+
+    /// Classifies one state. `height_m` is the altitude above the field;
+    /// `previous` is the phase currently shown, used for hysteresis and to
+    /// tell a landing roll from a takeoff roll.
+    pub fn classify(
+        on_ground: bool,
+        height_m: f64,
+        vertical_rate: f64,
+        velocity: f64,
+        previous: Option<FlightPhase>,
+    ) -> FlightPhase {
+        use FlightPhase::*;
+
+        if on_ground {
+            return if velocity < ROLL_SPEED {
+                Taxi
+            } else if matches!(previous, Some(Descent | Approach | Landing)) {
+                Landing
+            } else {
+                Takeoff
+            };
+        }
+
+        // Staying in a climb or descent takes less than entering one.
+        let climb_threshold = match previous {
+            Some(Takeoff | Climb) => EXIT_VERTICAL_RATE,
+            _ => ENTER_VERTICAL_RATE,
+        };
+        let descent_threshold = match previous {
+            Some(Descent | Approach | Landing) => EXIT_VERTICAL_RATE,
+            _ => ENTER_VERTICAL_RATE,
+        };
+
+        if vertical_rate >= climb_threshold {
+            if height_m < RUNWAY_HEIGHT {
+                Takeoff
+            } else {
+                Climb
+            }
+        } else if vertical_rate <= -descent_threshold {
+            if height_m < RUNWAY_HEIGHT {
+                Landing
+            } else if height_m < APPROACH_HEIGHT {
+                Approach
+            } else {
+                Descent
+            }
+        } else if height_m >= CRUISE_HEIGHT {
+            Cruise
+        } else {
+            // Level at low altitude: a level-off during the descent or on
+            // approach keeps its phase; otherwise call it cruise.
+            match previous {
+                Some(p @ (Descent | Approach)) => p,
+                _ => Cruise,
+            }
+        }
+    }
+
+    /// Whether this phase is part of a departure.
+    pub fn is_departure(self) -> bool {
+        matches!(self, FlightPhase::Takeoff | FlightPhase::Climb)
+    }
+
+    /// Whether this phase is part of an arrival.
+    pub fn is_arrival(self) -> bool {
+        matches!(
+            self,
+            FlightPhase::Descent | FlightPhase::Approach | FlightPhase::Landing
+        )
+    }
+}
+
+/// Which phases the flight list shows; maps to `[ui] phase_filter`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PhaseFilter {
+    #[default]
+    All,
+    /// Takeoff and climb.
+    Departures,
+    /// Descent, approach and landing.
+    Arrivals,
+    /// Departures and arrivals.
+    Terminal,
+    /// Taxiing aircraft.
+    Ground,
+    /// Cruise only.
+    Enroute,
+}
+
+impl PhaseFilter {
+    /// Name shown in the UI.
+    pub fn label(self) -> &'static str {
+        match self {
+            PhaseFilter::All => "All",
+            PhaseFilter::Departures => "Departures",
+            PhaseFilter::Arrivals => "Arrivals",
+            PhaseFilter::Terminal => "Departures + Arrivals",
+            PhaseFilter::Ground => "Ground",
+            PhaseFilter::Enroute => "En route",
+        }
+    }
+
+    /// The next filter, for cycling.
+    pub fn next(self) -> Self {
+        match self {
+            PhaseFilter::All => PhaseFilter::Departures,
+            PhaseFilter::Departures => PhaseFilter::Arrivals,
+            PhaseFilter::Arrivals => PhaseFilter::Terminal,
+            PhaseFilter::Terminal => PhaseFilter::Ground,
+            PhaseFilter::Ground => PhaseFilter::Enroute,
+            PhaseFilter::Enroute => PhaseFilter::All,
+        }
+    }
+
+    /// Whether an aircraft in `phase` passes the filter. Aircraft whose
+    /// phase is not known yet pass only [`PhaseFilter::All`].
+    pub fn matches(self, phase: Option<FlightPhase>) -> bool {
+        let Some(phase) = phase else {
+            return self == PhaseFilter::All;
+        };
+        match self {
+            PhaseFilter::All => true,
+            PhaseFilter::Departures => phase.is_departure(),
+            PhaseFilter::Arrivals => phase.is_arrival(),
+            PhaseFilter::Terminal => phase.is_departure() || phase.is_arrival(),
+            PhaseFilter::Ground => phase == FlightPhase::Taxi,
+            PhaseFilter::Enroute => phase == FlightPhase::Cruise,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PhaseState {
+    shown: FlightPhase,
+    /// Phase waiting out its dwell time, and when it was first seen.
+    candidate: Option<(FlightPhase, i64)>,
+}
+
+/// Debounced flight phase of every aircraft, keyed by ICAO24.
+#[derive(Debug, Default)]
+pub struct PhaseTracker {
+    states: HashMap<String, PhaseState>,
+    /// Elevation (m) of the field phases are judged against; altitudes are
+    /// compared as height above it.
+    pub field_elevation_m: f64,
+}
+
+impl PhaseTracker {
+    /// Classifies each flight and updates its shown phase, dropping
+    /// aircraft no longer in `flights`.
+    pub fn update<'a>(
+        &mut self,
+        flights: &[Flight],
+        track_of: impl Fn(&str) -> Option<&'a Track>,
+        now: i64,
+    ) {
+        let present: HashSet<&str> = flights.iter().map(|f| f.icao24.as_str()).collect();
+        self.states
+            .retain(|icao, _| present.contains(icao.as_str()));
+        for flight in flights {
+            let vertical_rate = track_of(&flight.icao24)
+                .and_then(smoothed_vertical_rate)
+                .unwrap_or(flight.vertical_rate);
+            let state = self.states.get(&flight.icao24).copied();
+            let raw = FlightPhase::classify(
+                flight.on_ground,
+                flight.altitude as f64 - self.field_elevation_m,
+                vertical_rate,
+                flight.velocity as f64,
+                state.map(|s| s.shown),
+            );
+            let next = match state {
+                None => PhaseState {
+                    shown: raw,
+                    candidate: None,
+                },
+                Some(s) if raw == s.shown => PhaseState {
+                    candidate: None,
+                    ..s
+                },
+                Some(s) => match s.candidate {
+                    Some((phase, since)) if phase == raw => {
+                        if now - since >= MIN_DWELL_SECS {
+                            PhaseState {
+                                shown: raw,
+                                candidate: None,
+                            }
+                        } else {
+                            s
+                        }
+                    }
+                    _ => PhaseState {
+                        candidate: Some((raw, now)),
+                        ..s
+                    },
+                },
+            };
+            self.states.insert(flight.icao24.clone(), next);
+        }
+    }
+
+    /// Shown phase of an aircraft.
+    pub fn get(&self, icao24: &str) -> Option<FlightPhase> {
+        self.states.get(icao24).map(|s| s.shown)
+    }
+}
+
+/// Average vertical rate (m/s) over the last [`SMOOTHING_SECS`] of track,
+/// if the track spans at least a third of that.
+fn smoothed_vertical_rate(track: &Track) -> Option<f64> {
+    let latest = track.latest()?;
+    let oldest = track
+        .points()
+        .find(|p| latest.time - p.time <= SMOOTHING_SECS)?;
+    let dt = latest.time - oldest.time;
+    (dt >= SMOOTHING_SECS / 3).then(|| (latest.altitude - oldest.altitude) as f64 / dt as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use FlightPhase::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(FlightPhase::classify(true, 0.0, 0.0, 8.0, None), Taxi);
+        assert_eq!(FlightPhase::classify(true, 0.0, 0.0, 60.0, None), Takeoff);
+        assert_eq!(
+            FlightPhase::classify(true, 0.0, 0.0, 60.0, Some(Approach)),
+            Landing
+        );
+        assert_eq!(
+            FlightPhase::classify(false, 150.0, 8.0, 80.0, None),
+            Takeoff
+        );
+        assert_eq!(
+            FlightPhase::classify(false, 3000.0, 8.0, 150.0, None),
+            Climb
+        );
+        assert_eq!(
+            FlightPhase::classify(false, 11000.0, 0.0, 240.0, None),
+            Cruise
+        );
+        assert_eq!(
+            FlightPhase::classify(false, 5000.0, -6.0, 200.0, None),
+            Descent
+        );
+        assert_eq!(
+            FlightPhase::classify(false, 900.0, -4.0, 80.0, None),
+            Approach
+        );
+        assert_eq!(
+            FlightPhase::classify(false, 100.0, -3.5, 70.0, None),
+            Landing
+        );
+
+        // A shallow climb only continues one already under way.
+        assert_eq!(
+            FlightPhase::classify(false, 3000.0, 1.5, 150.0, None),
+            Cruise
+        );
+        assert_eq!(
+            FlightPhase::classify(false, 3000.0, 1.5, 150.0, Some(Climb)),
+            Climb
+        );
+    }
+
+    #[test]
+    fn test_phase_change_needs_dwell_time() {
+        let flight = |vertical_rate: f64| Flight {
+            icao24: "abc123".to_string(),
+            altitude: 3000.0,
+            velocity: 150.0,
+            vertical_rate,
+            ..Default::default()
+        };
+        let mut tracker = PhaseTracker::default();
+        tracker.update(&[flight(8.0)], |_| None, 0);
+        assert_eq!(tracker.get("abc123"), Some(Climb));
+
+        // A one-off descent reading does not flip the phase...
+        tracker.update(&[flight(-6.0)], |_| None, 10);
+        tracker.update(&[flight(8.0)], |_| None, 20);
+        assert_eq!(tracker.get("abc123"), Some(Climb));
+
+        // ...a sustained one does.
+        tracker.update(&[flight(-6.0)], |_| None, 30);
+        tracker.update(&[flight(-6.0)], |_| None, 40);
+        assert_eq!(tracker.get("abc123"), Some(Climb));
+        tracker.update(&[flight(-6.0)], |_| None, 50);
+        assert_eq!(tracker.get("abc123"), Some(Descent));
+
+        tracker.update(&[], |_| None, 60);
+        assert_eq!(tracker.get("abc123"), None);
+    }
+
+    #[test]
+    fn test_phases_are_relative_to_field_elevation() {
+        // A field at 1650 m, like Denver: every altitude nearby is above the
+        // sea-level runway and approach heights.
+        let flight = |icao: &str, altitude: f32, vertical_rate: f64| Flight {
+            icao24: icao.to_string(),
+            altitude,
+            velocity: 80.0,
+            vertical_rate,
+            ..Default::default()
+        };
+        let mut tracker = PhaseTracker {
+            field_elevation_m: 1650.0,
+            ..Default::default()
+        };
+        tracker.update(
+            &[
+                flight("dep001", 1800.0, 8.0),
+                flight("arr001", 1750.0, -3.5),
+                flight("arr002", 2600.0, -4.0),
+            ],
+            |_| None,
+            0,
+        );
+        assert_eq!(tracker.get("dep001"), Some(Takeoff));
+        assert_eq!(tracker.get("arr001"), Some(Landing));
+        assert_eq!(tracker.get("arr002"), Some(Approach));
+    }
+}
@@ -0,0 +1,216 @@
+//! Per-aircraft track history for the Redwood flight tracker.
+//!
+//! Each [`Event::FlightUpdate`](crate::events::Event::FlightUpdate) replaces
+//! the current flight list; [`TrackStore`] keeps what came before. For every
+//! ICAO24 it holds a bounded ring buffer of time-stamped [`TrackPoint`]s and
+//! drops aircraft that have not been seen for a configurable time. Radar
+//! trails, altitude charts and derived analytics read from here.
+
+use crate::config::TrackConfig;
+use crate::models::Flight;
+use std::collections::{HashMap, VecDeque};
+
+/// One recorded state of an aircraft.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackPoint {
+    /// Unix time (seconds) of the position.
+    pub time: i64,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Altitude in meters.
+    pub altitude: f32,
+    /// Ground speed in meters per second.
+    pub velocity: f32,
+    /// True track in degrees.
+    pub true_track: f32,
+    /// Vertical rate in meters per second.
+    pub vertical_rate: f64,
+}
+
+impl TrackPoint {
+    fn from_flight(flight: &Flight, time: i64) -> Self {
+        Self {
+            time,
+            latitude: flight.latitude,
+            longitude: flight.longitude,
+            altitude: flight.altitude,
+            velocity: flight.velocity,
+            true_track: flight.true_track,
+            vertical_rate: flight.vertical_rate,
+        }
+    }
+}
+
+/// History of one aircraft, oldest point first.
+#[derive(Debug, Clone, Default)]
+pub struct Track {
+    points: VecDeque<TrackPoint>,
+    /// Unix time (seconds) at which the aircraft was last reported.
+    last_seen: i64,
+}
+
+impl Track {
+    /// Recorded points, oldest first.
+    pub fn points(&self) -> impl DoubleEndedIterator<Item = &TrackPoint> + ExactSizeIterator {
+        self.points.iter()
+    }
+
+    /// Most recent point.
+    pub fn latest(&self) -> Option<&TrackPoint> {
+        self.points.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+/// Track history of every aircraft, keyed by lowercase ICAO24.
+#[derive(Debug)]
+pub struct TrackStore {
+    tracks: HashMap<String, Track>,
+    /// Points kept per aircraft; the oldest is dropped beyond this.
+    max_points: usize,
+    /// Seconds without a report after which an aircraft is forgotten.
+    expire_after_secs: i64,
+}
+
+impl Default for TrackStore {
+    fn default() -> Self {
+        Self::new(&TrackConfig::default())
+    }
+}
+
+impl TrackStore {
+    pub fn new(config: &TrackConfig) -> Self {
+        Self {
+            tracks: HashMap::new(),
+            max_points: config.max_points.max(1),
+            expire_after_secs: config.expire_after_seconds as i64,
+        }
+    }
+
+    /// Appends the current state of each flight, then expires aircraft not
+    /// seen for the configured time.
+    ///
+    /// A point is stamped with the flight's latest report (last contact or
+    /// position time, or `now`). A report no newer than the last point, or
+    /// identical to it apart from the time, is not recorded again; an
+    /// altitude or speed change at the same position is.
+    pub fn record(&mut self, flights: &[Flight], now: i64) {
+        for flight in flights {
+            let time = flight.last_contact.max(flight.time_position).unwrap_or(now);
+            let point = TrackPoint::from_flight(flight, time);
+            let track = self.tracks.entry(flight.icao24.clone()).or_default();
+            track.last_seen = now;
+
+            let unchanged = track
+                .latest()
+                .is_some_and(|last| time <= last.time || TrackPoint { time, ..*last } == point);
+            if unchanged {
+                continue;
+            }
+            track.points.push_back(point);
+            while track.points.len() > self.max_points {
+                track.points.pop_front();
+            }
+        }
+        self.expire(now);
+    }
+
+    /// Drops aircraft not reported within the expiry time.
+    pub fn expire(&mut self, now: i64) {
+        let ttl = self.expire_after_secs;
+        self.tracks.retain(|_, track| now - track.last_seen <= ttl);
+    }
+
+    /// History of one aircraft.
+    pub fn get(&self, icao24: &str) -> Option<&Track> {
+        self.tracks.get(icao24)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tracks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flight(icao: &str, lat: f64, time: i64) -> Flight {
+        Flight {
+            icao24: icao.to_string(),
+            latitude: lat,
+            longitude: -122.0,
+            time_position: Some(time),
+            ..Default::default()
+        }
+    }
+
+    fn store(max_points: usize, expire_after_seconds: u64) -> TrackStore {
+        TrackStore::new(&TrackConfig {
+            max_points,
+            expire_after_seconds,
+        })
+    }
+
+    #[test]
+    fn test_ring_buffer_keeps_newest_points() {
+        let mut tracks = store(3, 300);
+        for t in 0..5 {
+            tracks.record(&[flight("abc123", 37.0 + t as f64 * 0.01, t)], t);
+        }
+        // A repeated report of the same position adds nothing.
+        tracks.record(&[flight("abc123", 37.04, 4)], 5);
+
+        let track = tracks.get("abc123").unwrap();
+        let times: Vec<i64> = track.points().map(|p| p.time).collect();
+        assert_eq!(times, vec![2, 3, 4]);
+        assert!((track.latest().unwrap().latitude - 37.04).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_same_position_keeps_other_changes() {
+        let mut tracks = store(10, 300);
+        tracks.record(&[flight("abc123", 37.0, 10)], 10);
+        // A Mode-S altitude reply: newer contact, same reported position.
+        let climbed = Flight {
+            altitude: 1500.0,
+            last_contact: Some(12),
+            ..flight("abc123", 37.0, 10)
+        };
+        tracks.record(std::slice::from_ref(&climbed), 12);
+        // Heard again with nothing new.
+        tracks.record(
+            &[Flight {
+                last_contact: Some(14),
+                ..climbed
+            }],
+            14,
+        );
+
+        let track = tracks.get("abc123").unwrap();
+        let points: Vec<(i64, f32)> = track.points().map(|p| (p.time, p.altitude)).collect();
+        assert_eq!(points, vec![(10, 0.0), (12, 1500.0)]);
+    }
+
+    #[test]
+    fn test_unseen_aircraft_expire() {
+        let mut tracks = store(10, 60);
+        tracks.record(&[flight("aaaaaa", 37.0, 0), flight("bbbbbb", 38.0, 0)], 0);
+        tracks.record(&[flight("bbbbbb", 38.1, 50)], 50);
+        tracks.record(&[flight("bbbbbb", 38.2, 90)], 90);
+
+        assert!(tracks.get("aaaaaa").is_none());
+        assert_eq!(tracks.get("bbbbbb").unwrap().len(), 3);
+    }
+}
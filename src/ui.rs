@@ -3,13 +3,21 @@
 //! This module handles all UI rendering logic using the `ratatui` crate,
 //! including dashboard views, spotter views, loading screens, and settings.
 
-use crate::app::{App, ViewMode};
+use crate::alerts::AlertKind;
+use crate::api::SourceHealth;
+use crate::app::{App, SortMode, ViewMode};
+use crate::classify::AircraftClass;
+use crate::db::DbStatus;
+use crate::icao::{self, AddressBlock};
+use crate::models::Flight;
+use crate::phase::PhaseFilter;
 use ratatui::{
     prelude::*,
     widgets::{canvas::*, *}, // Imports Points, Circle, Map, etc.
 };
 
 use ratatui::text::Line;
+use std::time::Duration;
 
 /// Renders one frame of the TUI based on current application state.
 ///
@@ -27,20 +35,25 @@ pub fn render(f: &mut Frame, app: &App) {
         render_loading_screen(f, app);
         return;
     }
+    if let Some(reason) = &app.rebuild_prompt {
+        render_rebuild_prompt(f, reason);
+        return;
+    }
 
     match app.view_mode {
         ViewMode::Dashboard => render_dashboard_view(f, app),
         ViewMode::Spotter => render_spotter_view(f, app),
         ViewMode::Settings => render_settings_view(f, app),
         ViewMode::Radar => render_radar_view(f, app),
+        ViewMode::Alerts => render_alerts_view(f, app),
     }
 }
 
 /// Dashboard view: flight list sidebar (30%) + main area (70%).
 ///
 /// The main area is split into a fixed-height telemetry block and a details
-/// paragraph. Shows "Flights Nearby" list, system telemetry (network, latency,
-/// DB hits, selected ICAO, enriched vs raw), and detailed identity/telemetry
+/// paragraph. Shows "Flights Nearby" list, system telemetry (source health,
+/// latency, DB hits, selected ICAO, enriched vs raw, source name), and detailed identity/telemetry
 /// for the selected flight.
 ///
 /// # Arguments
@@ -59,11 +72,17 @@ fn render_dashboard_view(f: &mut Frame, app: &App) {
         .iter()
         .enumerate()
         .map(|(i, fl)| {
-            let style = if i == app.selected_index {
+            let alert = app.alert_for(fl);
+            let class = AircraftClass::of(fl);
+            let style = if let Some(kind) = alert {
+                alert_style(app, kind)
+            } else if i == app.selected_index {
                 Style::default()
                     .fg(Color::Cyan)
                     .bg(Color::Rgb(30, 30, 60))
                     .add_modifier(Modifier::BOLD)
+            } else if let Some(color) = class_color(class) {
+                Style::default().fg(color)
             } else {
                 Style::default()
             };
@@ -79,19 +98,47 @@ fn render_dashboard_view(f: &mut Frame, app: &App) {
             let op = fl.operator.as_deref().unwrap_or("???");
             let short_op = if op.len() > 12 { &op[..12] } else { op };
 
-            ListItem::new(Line::from(vec![
-                Span::styled(format!(" {:<8}", id), style),
+            let icon = app.phase_for(fl).map_or(" ", |p| p.icon());
+            let mut spans = vec![
+                Span::styled(format!(" {} {:<8}", icon, id), style),
                 Span::styled(
                     format!(" │ {}", short_op),
                     Style::default().fg(Color::DarkGray),
                 ),
-            ]))
+            ];
+            if let Some(color) = class_color(class) {
+                spans.push(Span::styled(
+                    format!(" {}", class.tag()),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ));
+            }
+            if let Some(kind) = alert {
+                spans.push(Span::styled(format!(" ⚠ {}", kind.label()), style));
+            } else if let Some(eta) = overhead_eta(app, fl) {
+                spans.push(Span::styled(
+                    format!(" ▲{}", eta),
+                    Style::default().fg(Color::LightGreen),
+                ));
+            }
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
     let list = List::new(items).block(
         Block::default()
-            .title(" Flights Nearby ")
+            .title(format!(
+                " Flights Nearby ({}{}{}) ",
+                app.sort_mode.label(),
+                match app.config.ui.phase_filter {
+                    PhaseFilter::All => String::new(),
+                    filter => format!(", {}", filter.label()),
+                },
+                if app.config.ui.military_only {
+                    ", mil/gov only"
+                } else {
+                    ""
+                }
+            ))
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded),
     );
@@ -118,10 +165,61 @@ fn render_dashboard_view(f: &mut Frame, app: &App) {
             Color::Red
         };
 
+        let network_health = app.network_health();
+        let units = app.units();
+
+        let mut feeds = vec![Span::styled(
+            "  FEEDS: ",
+            Style::default().add_modifier(Modifier::BOLD),
+        )];
+        for (i, (name, health)) in app.source_health.iter().enumerate() {
+            if i > 0 {
+                feeds.push(Span::raw("  │  "));
+            }
+            feeds.push(Span::raw(format!("{} ", name)));
+            feeds.push(Span::styled(
+                health.label(),
+                Style::default().fg(health_color(health)),
+            ));
+            if let Some(remaining) = app
+                .source_rate_limits
+                .get(name)
+                .and_then(|limit| limit.remaining)
+            {
+                feeds.push(Span::styled(
+                    format!(" ({} credits)", remaining),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+        }
+
+        let mut polls = vec![Span::styled(
+            "  POLL:  ",
+            Style::default().add_modifier(Modifier::BOLD),
+        )];
+        for (i, (name, schedule)) in app.poll_schedules.iter().enumerate() {
+            if i > 0 {
+                polls.push(Span::raw("  │  "));
+            }
+            let next = schedule.next_poll.saturating_duration_since(now);
+            polls.push(Span::raw(format!(
+                "{} every {} ",
+                name,
+                format_interval(schedule.interval)
+            )));
+            polls.push(Span::styled(
+                format!("(next in {})", format_interval(next)),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+
         let stats_content = vec![
             Line::from(vec![
                 Span::styled("  NETWORK: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::styled("ONLINE", Style::default().fg(Color::Green)),
+                Span::styled(
+                    network_health.label(),
+                    Style::default().fg(health_color(&network_health)),
+                ),
                 Span::raw("  │  "),
                 Span::styled("LATENCY: ", Style::default().add_modifier(Modifier::BOLD)),
                 Span::styled(
@@ -155,8 +253,16 @@ fn render_dashboard_view(f: &mut Frame, app: &App) {
                 Span::styled(&app.tracking_region, Style::default().fg(Color::Magenta)),
                 Span::raw("  │  "),
                 Span::styled("RANGE: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw(format!("{}km", app.config.location.detection_radius)),
+                Span::raw(units.distance(app.config.location.detection_radius)),
+                Span::raw("  │  "),
+                Span::styled("SOURCE: ", Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    fl.source.as_deref().unwrap_or("---"),
+                    Style::default().fg(Color::Cyan),
+                ),
             ]),
+            Line::from(feeds),
+            Line::from(polls),
         ];
 
         let stats_block = Paragraph::new(stats_content)
@@ -174,7 +280,9 @@ fn render_dashboard_view(f: &mut Frame, app: &App) {
     // Flight Details
     if let Some(fl) = app.flights.get(app.selected_index) {
         let operator = fl.operator.as_deref().unwrap_or("Private/Unknown");
-        let op_color = get_operator_color(operator);
+        let op_color = class_color(AircraftClass::of(fl)).unwrap_or(get_operator_color(operator));
+        let look = fl.look_angles(&app.observer());
+        let units = app.units();
 
         let details = vec![
             Line::from(vec![
@@ -216,6 +324,22 @@ fn render_dashboard_view(f: &mut Frame, app: &App) {
                     fl.aircraft_type.as_deref().unwrap_or("---")
                 )),
             ]),
+            Line::from(vec![
+                Span::styled(
+                    "Category:     ",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(fl.category_label().unwrap_or("Unknown")),
+            ]),
+            Line::from(vec![
+                Span::styled(
+                    "Phase:        ",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(app.phase_for(fl).map_or("Unknown".to_string(), |p| {
+                    format!("{} {}", p.icon(), p.label())
+                })),
+            ]),
             Line::from(""),
             Line::from(vec![
                 Span::styled(
@@ -223,10 +347,58 @@ fn render_dashboard_view(f: &mut Frame, app: &App) {
                     Style::default().add_modifier(Modifier::BOLD),
                 ),
                 Span::raw(format!(
-                    "{:.0} m  |  {:.0} km/h  |  {:.0}°",
-                    fl.altitude, fl.velocity, fl.true_track
+                    "{}  |  {}  |  {:.0}°  |  {}",
+                    units.altitude(fl.altitude as f64),
+                    units.speed(fl.velocity as f64),
+                    fl.true_track,
+                    units.vertical_rate(fl.vertical_rate)
                 )),
             ]),
+            Line::from(vec![
+                Span::styled(
+                    "Look Angles:  ",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!(
+                    "AZ {:.0}° {}  |  EL {:.1}°{}  |  {} slant",
+                    look.azimuth_deg,
+                    compass_point(look.azimuth_deg),
+                    look.elevation_deg,
+                    if look.elevation_deg < 0.0 {
+                        " (below horizon)"
+                    } else {
+                        ""
+                    },
+                    units.distance(look.slant_range_km)
+                )),
+            ]),
+            Line::from(vec![
+                Span::styled(
+                    "Transponder:  ",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!(
+                    "Squawk {}  |  {}  |  {}{}",
+                    fl.squawk.as_deref().unwrap_or("----"),
+                    if fl.on_ground {
+                        "On Ground"
+                    } else {
+                        "Airborne"
+                    },
+                    fl.position_source.map_or("---", |p| p.label()),
+                    if fl.spi { "  |  IDENT" } else { "" }
+                )),
+            ]),
+            Line::from(vec![
+                Span::styled(
+                    "Alert:        ",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                match app.alert_for(fl) {
+                    Some(kind) => Span::styled(kind.label(), alert_style(app, kind)),
+                    None => Span::styled("None", Style::default().fg(Color::DarkGray)),
+                },
+            ]),
             Line::from(vec![
                 Span::styled(
                     "Origin:       ",
@@ -234,6 +406,25 @@ fn render_dashboard_view(f: &mut Frame, app: &App) {
                 ),
                 Span::raw(&fl.origin_country),
             ]),
+            Line::from(vec![
+                Span::styled(
+                    "Address:      ",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                match icao::lookup(&fl.icao24) {
+                    _ if fl.non_icao => Span::raw("Non-ICAO (TIS-B)"),
+                    Some(block) if block.military => Span::styled(
+                        format!("{} block, MILITARY", block.country.unwrap_or("Unassigned")),
+                        Style::default().fg(Color::LightRed),
+                    ),
+                    Some(AddressBlock {
+                        country: Some(country),
+                        ..
+                    }) => Span::raw(format!("{} block", country)),
+                    Some(_) => Span::styled("Unassigned block", Style::default().fg(Color::Yellow)),
+                    None => Span::styled("Invalid address", Style::default().fg(Color::Yellow)),
+                },
+            ]),
         ];
 
         let p = Paragraph::new(details).block(
@@ -257,6 +448,7 @@ fn render_radar_view(f: &mut Frame, app: &App) {
 
     let (u_lat, u_lon) = app.user_coords;
     let radius = 1.0; // Your zoom level
+    let units = app.units();
 
     let radar_canvas = Canvas::default()
         .block(Block::bordered().title(" Precision Radar "))
@@ -316,11 +508,41 @@ fn render_radar_view(f: &mut Frame, app: &App) {
                                     .add_modifier(Modifier::BOLD),
                             ),
                             Span::styled(
-                                format!(" {} ", flight.callsign),
+                                format!(
+                                    " {} {}{} ",
+                                    flight.callsign,
+                                    units.altitude(flight.altitude as f64),
+                                    if flight.extrapolated { " (est)" } else { "" }
+                                ),
                                 Style::default().fg(Color::Black).bg(Color::Yellow),
                             ),
                         ]),
                     );
+                } else if let Some(kind) = app.alert_for(flight) {
+                    ctx.print(
+                        flight.longitude,
+                        flight.latitude,
+                        Line::from(Span::styled(
+                            format!(" ✈ {} {} ", flight.callsign, kind.label()),
+                            alert_style(app, kind),
+                        )),
+                    );
+                } else if let Some(color) = class_color(AircraftClass::of(flight)) {
+                    ctx.print(
+                        flight.longitude,
+                        flight.latitude,
+                        Line::from(Span::styled(
+                            format!("✈ {}", flight.callsign.trim()),
+                            Style::default().fg(color),
+                        )),
+                    );
+                } else if flight.extrapolated {
+                    // Dead-reckoned between reports.
+                    ctx.print(
+                        flight.longitude,
+                        flight.latitude,
+                        Line::from(Span::styled("∘", Style::default().fg(Color::Gray))),
+                    );
                 } else {
                     ctx.print(flight.longitude, flight.latitude, "·");
                 }
@@ -358,6 +580,17 @@ fn render_spotter_view(f: &mut Frame, app: &App) {
                 Constraint::Percentage(20),
             ])
             .split(area);
+        if let Some(kind) = app.alert_for(target) {
+            f.render_widget(
+                Paragraph::new(Line::from(Span::styled(
+                    format!(" ⚠ {} ⚠ ", kind.label()),
+                    alert_style(app, kind),
+                )))
+                .alignment(Alignment::Center),
+                chunks[0],
+            );
+        }
+
         // ID Block - the big center block.
         let id_text = vec![
             Line::from(Span::styled(
@@ -374,6 +607,12 @@ fn render_spotter_view(f: &mut Frame, app: &App) {
                     .add_modifier(Modifier::BOLD),
             )),
             Line::from(target.model.as_deref().unwrap_or("Unknown Aircraft")),
+            Line::from(""),
+            Line::from(Span::styled(
+                app.phase_for(target)
+                    .map_or(String::new(), |p| format!("{} {}", p.icon(), p.label())),
+                Style::default().fg(Color::LightBlue),
+            )),
         ];
 
         f.render_widget(
@@ -384,10 +623,53 @@ fn render_spotter_view(f: &mut Frame, app: &App) {
         );
 
         // Telemetry - the bottom bar
-        let telemetry = Paragraph::new(format!(
-            "Altitude: {} m | Velocity: {} km/h | Heading: {}°",
-            target.altitude, target.velocity, target.true_track
-        ))
+        let look = target.look_angles(&app.observer());
+        let cpa = app.closest_approach(target);
+        let units = app.units();
+        let approach = if cpa.time_secs > 0.0 {
+            format!(
+                "Passes {} {} in {}",
+                units.distance(cpa.distance_km),
+                compass_name(cpa.bearing_deg),
+                format_interval(Duration::from_secs_f64(cpa.time_secs))
+            )
+        } else {
+            format!(
+                "Closest now, {} and moving away",
+                units.distance(cpa.distance_km)
+            )
+        };
+        let telemetry = Paragraph::new(vec![
+            Line::from(format!(
+                "Altitude: {} | Speed: {} | Heading: {:.0}° | V/S: {}",
+                units.altitude(target.altitude as f64),
+                units.speed(target.velocity as f64),
+                target.true_track,
+                units.vertical_rate(target.vertical_rate)
+            )),
+            Line::from(Span::styled(
+                format!(
+                    "Look: AZ {:.0}° {}  EL {:.1}°  Range {}",
+                    look.azimuth_deg,
+                    compass_point(look.azimuth_deg),
+                    look.elevation_deg,
+                    units.distance(look.slant_range_km)
+                ),
+                Style::default().fg(if look.elevation_deg > 0.0 {
+                    Color::Cyan
+                } else {
+                    Color::DarkGray
+                }),
+            )),
+            Line::from(Span::styled(
+                approach,
+                Style::default().fg(if app.passes_overhead(&cpa) {
+                    Color::LightGreen
+                } else {
+                    Color::DarkGray
+                }),
+            )),
+        ])
         .alignment(Alignment::Center)
         .style(Style::default().fg(Color::DarkGray));
 
@@ -428,6 +710,96 @@ fn render_loading_screen(f: &mut Frame, app: &App) {
     f.render_widget(msg, chunks[2]);
 }
 
+/// Startup prompt shown when the aircraft DB exists but failed
+/// [`check_database`](crate::db::check_database): explains why and offers
+/// a rebuild.
+fn render_rebuild_prompt(f: &mut Frame, reason: &str) {
+    let area = f.size();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(area.height.saturating_sub(7) / 2),
+            Constraint::Length(7),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    let text = vec![
+        Line::from("The aircraft database looks incomplete or out of date:"),
+        Line::from(Span::styled(
+            reason.to_string(),
+            Style::default().fg(Color::Yellow),
+        )),
+        Line::from(""),
+        Line::from("Rebuild it now? [y] rebuild  [n] keep using it  [q] quit"),
+    ];
+    let prompt = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .title(" Aircraft Database ")
+                .borders(Borders::ALL),
+        );
+    f.render_widget(prompt, chunks[1]);
+}
+
+/// Alerts view: open alerts (flashing) above the history of cleared ones,
+/// newest first, so an alert that has already cleared can still be reviewed.
+fn render_alerts_view(f: &mut Frame, app: &App) {
+    let time = |secs: i64| {
+        chrono::DateTime::from_timestamp(secs, 0)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_else(|| "--:--:--".to_string())
+    };
+
+    let header = Row::new(["Raised", "Cleared", "Callsign", "ICAO24", "Alert"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let active = app.alerts.active().into_iter().map(|alert| {
+        Row::new([
+            time(alert.raised_at),
+            "ACTIVE".to_string(),
+            alert.callsign.clone(),
+            alert.icao24.clone(),
+            alert.kind.label().to_string(),
+        ])
+        .style(alert_style(app, alert.kind))
+    });
+    let history = app.alerts.history().map(|alert| {
+        Row::new([
+            time(alert.raised_at),
+            alert.cleared_at.map(time).unwrap_or_default(),
+            alert.callsign.clone(),
+            alert.icao24.clone(),
+            alert.kind.label().to_string(),
+        ])
+        .style(Style::default().fg(Color::DarkGray))
+    });
+
+    let table = Table::new(
+        active.chain(history),
+        [
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Min(16),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .title(" Alerts (squawk 7500 / 7600 / 7700, ident, mil/gov) ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded),
+    );
+    f.render_widget(table, f.size());
+}
+
 /// Settings view: displays config and allows editing with ↑/↓, Enter/Space, +/-.
 fn render_settings_view(f: &mut Frame, app: &App) {
     let area = f.size();
@@ -448,7 +820,29 @@ fn render_settings_view(f: &mut Frame, app: &App) {
 
     let c = &app.config;
     let sel = app.settings_selected_index;
-    let rows: [(usize, &str, String); 6] = [
+    let units = app.units();
+    let db_state = match &app.db_status {
+        DbStatus::Missing => "Missing".to_string(),
+        DbStatus::Ready(meta) => {
+            let (verb, at) = match meta.imported_at {
+                Some(imported_at) => ("updated", imported_at),
+                None => ("built", meta.built_at),
+            };
+            let date = chrono::DateTime::from_timestamp(at, 0)
+                .map(|t| {
+                    t.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            format!("{} aircraft, {} {}", meta.row_count, verb, date)
+        }
+        DbStatus::Invalid(_) => "Needs rebuild".to_string(),
+    };
+    let newest = crate::db::newest_csv(std::path::Path::new(crate::db::DATA_DIR))
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "No CSV in data/".to_string());
+    let rows: [(usize, &str, String); 15] = [
         (
             0,
             "Use IP geolocation     ",
@@ -466,8 +860,8 @@ fn render_settings_view(f: &mut Frame, app: &App) {
         ),
         (
             3,
-            "Detection radius (km)  ",
-            format!("{:.0}", c.location.detection_radius),
+            "Detection radius       ",
+            units.distance(c.location.detection_radius),
         ),
         (
             4,
@@ -475,6 +869,39 @@ fn render_settings_view(f: &mut Frame, app: &App) {
             c.api.poll_interval_seconds.to_string(),
         ),
         (5, "Default view          ", c.ui.default_view.clone()),
+        (
+            6,
+            "Observer altitude      ",
+            units.altitude(c.location.observer_altitude_m),
+        ),
+        (
+            7,
+            "Min elevation (°)      ",
+            format!("{:.0}", c.ui.min_elevation_deg),
+        ),
+        (
+            8,
+            "Hide below min elev.   ",
+            if c.ui.horizon_filter { "Yes" } else { "No" }.to_string(),
+        ),
+        (9, "Units                  ", c.ui.units.label().to_string()),
+        (
+            10,
+            "Phase filter           ",
+            c.ui.phase_filter.label().to_string(),
+        ),
+        (
+            11,
+            "Mil/gov aircraft only  ",
+            if c.ui.military_only { "Yes" } else { "No" }.to_string(),
+        ),
+        (
+            12,
+            "Alert on mil/gov       ",
+            if c.ui.military_alert { "Yes" } else { "No" }.to_string(),
+        ),
+        (13, "Aircraft DB (rebuild)  ", db_state),
+        (14, "Import newest CSV      ", newest),
     ];
     let items: Vec<Line> = rows
         .iter()
@@ -525,22 +952,116 @@ fn draw_flight_sidebar(f: &mut Frame, app: &App, area: Rect) {
         .iter()
         .enumerate()
         .map(|(i, f)| {
-            let style = if Some(i) == Some(app.selected_index) {
+            let alert = app.alert_for(f);
+            let style = if let Some(kind) = alert {
+                alert_style(app, kind)
+            } else if Some(i) == Some(app.selected_index) {
                 Style::default().fg(Color::Black).bg(Color::Yellow)
+            } else if let Some(color) = class_color(AircraftClass::of(f)) {
+                Style::default().fg(color)
             } else {
                 Style::default()
             };
-            ListItem::new(format!(" > {}", f.callsign)).style(style)
+            let eta = match alert {
+                Some(kind) => format!(" ⚠ {}", kind.label()),
+                None => overhead_eta(app, f)
+                    .map(|eta| format!(" ▲{}", eta))
+                    .unwrap_or_default(),
+            };
+            ListItem::new(format!(" > {}{}", f.callsign, eta)).style(style)
         })
         .collect();
 
     let list = List::new(items)
-        .block(Block::bordered().title("Flights"))
+        .block(Block::bordered().title(match app.sort_mode {
+            SortMode::Distance => "Flights",
+            SortMode::ClosestApproach => "Flights (by approach)",
+        }))
         .highlight_symbol(">> ");
 
     f.render_widget(list, area);
 }
 
+/// Formats a poll interval compactly: `"750ms"`, `"30s"`, `"2m05s"`.
+fn format_interval(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs == 0 && d.as_millis() > 0 {
+        format!("{}ms", d.as_millis())
+    } else if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+/// Eight-point compass name for a bearing in degrees.
+fn compass_point(bearing_deg: f64) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    POINTS[((bearing_deg.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8]
+}
+
+/// Flashing style for an aircraft with an open alert: emergencies blink
+/// white on red, ident blinks black on yellow.
+fn alert_style(app: &App, kind: AlertKind) -> Style {
+    let color = match kind {
+        AlertKind::Ident => Color::Yellow,
+        AlertKind::Military | AlertKind::Government => Color::Magenta,
+        _ => Color::Red,
+    };
+    let style = Style::default().add_modifier(Modifier::BOLD);
+    if app.flash_on() {
+        style
+            .fg(if color == Color::Yellow {
+                Color::Black
+            } else {
+                Color::White
+            })
+            .bg(color)
+    } else {
+        style.fg(color)
+    }
+}
+
+/// Color for military and government aircraft; `None` for civil ones.
+fn class_color(class: AircraftClass) -> Option<Color> {
+    match class {
+        AircraftClass::Civil => None,
+        AircraftClass::Government => Some(Color::LightBlue),
+        AircraftClass::Military => Some(Color::Rgb(150, 170, 60)),
+    }
+}
+
+/// Full lowercase compass name for a bearing, e.g. `"northeast"`.
+fn compass_name(bearing_deg: f64) -> &'static str {
+    match compass_point(bearing_deg) {
+        "N" => "north",
+        "NE" => "northeast",
+        "E" => "east",
+        "SE" => "southeast",
+        "S" => "south",
+        "SW" => "southwest",
+        "W" => "west",
+        _ => "northwest",
+    }
+}
+
+/// Time until `flight` passes overhead, if it is predicted to within the
+/// configured radius and window.
+fn overhead_eta(app: &App, flight: &Flight) -> Option<String> {
+    let cpa = app.closest_approach(flight);
+    app.passes_overhead(&cpa)
+        .then(|| format_interval(Duration::from_secs_f64(cpa.time_secs)))
+}
+
+/// Color for a source health label: green online, yellow pending, red error.
+fn health_color(health: &SourceHealth) -> Color {
+    match health {
+        SourceHealth::Online => Color::Green,
+        SourceHealth::Pending => Color::Yellow,
+        SourceHealth::Error(_) => Color::Red,
+    }
+}
+
 /// Returns a color associated with the operator name for brand-style display.
 ///
 /// Matches common US airlines and cargo operators by substring (case-insensitive).
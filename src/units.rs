@@ -0,0 +1,222 @@
+//! Display units for the Redwood flight tracker.
+//!
+//! Flight data is kept in SI units throughout (metres, m/s, kilometres); the
+//! views convert only when formatting. [`Units`] is resolved from
+//! [`UiConfig`]: a [`UnitSystem`] preset, with each quantity optionally
+//! overridden, e.g. aviation units but distances in kilometres.
+
+use crate::config::UiConfig;
+use serde::{Deserialize, Serialize};
+
+const FEET_PER_METRE: f64 = 3.280_84;
+const KM_PER_MILE: f64 = 1.609_344;
+const KM_PER_NAUTICAL_MILE: f64 = 1.852;
+
+/// Unit preset; maps to `[ui] units`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    /// Metres, km/h, kilometres, m/s.
+    #[default]
+    Metric,
+    /// Feet, mph, statute miles, ft/min.
+    Imperial,
+    /// Feet, knots, nautical miles, ft/min.
+    Aviation,
+}
+
+impl UnitSystem {
+    /// Name shown in Settings.
+    pub fn label(self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "Metric",
+            UnitSystem::Imperial => "Imperial",
+            UnitSystem::Aviation => "Aviation",
+        }
+    }
+
+    /// The next preset, for cycling in Settings.
+    pub fn next(self) -> Self {
+        match self {
+            UnitSystem::Metric => UnitSystem::Imperial,
+            UnitSystem::Imperial => UnitSystem::Aviation,
+            UnitSystem::Aviation => UnitSystem::Metric,
+        }
+    }
+}
+
+/// Altitude display unit; `[ui] altitude_unit`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AltitudeUnit {
+    #[serde(rename = "m", alias = "metres")]
+    Metres,
+    #[serde(rename = "ft", alias = "feet")]
+    Feet,
+}
+
+/// Ground speed display unit; `[ui] speed_unit`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeedUnit {
+    #[serde(rename = "km/h", alias = "kmh")]
+    KilometresPerHour,
+    Mph,
+    #[serde(rename = "kt", alias = "knots")]
+    Knots,
+    #[serde(rename = "m/s", alias = "ms")]
+    MetresPerSecond,
+}
+
+/// Distance display unit; `[ui] distance_unit`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceUnit {
+    #[serde(rename = "km", alias = "kilometres")]
+    Kilometres,
+    #[serde(rename = "mi", alias = "miles")]
+    Miles,
+    #[serde(rename = "nm", alias = "nautical_miles")]
+    NauticalMiles,
+}
+
+/// Climb/descent rate display unit; `[ui] vertical_rate_unit`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerticalRateUnit {
+    #[serde(rename = "m/s", alias = "ms")]
+    MetresPerSecond,
+    #[serde(rename = "ft/min", alias = "fpm")]
+    FeetPerMinute,
+}
+
+/// Resolved display unit for each quantity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Units {
+    pub altitude: AltitudeUnit,
+    pub speed: SpeedUnit,
+    pub distance: DistanceUnit,
+    pub vertical_rate: VerticalRateUnit,
+}
+
+impl Units {
+    /// Units of a preset with no overrides.
+    pub fn preset(system: UnitSystem) -> Self {
+        match system {
+            UnitSystem::Metric => Self {
+                altitude: AltitudeUnit::Metres,
+                speed: SpeedUnit::KilometresPerHour,
+                distance: DistanceUnit::Kilometres,
+                vertical_rate: VerticalRateUnit::MetresPerSecond,
+            },
+            UnitSystem::Imperial => Self {
+                altitude: AltitudeUnit::Feet,
+                speed: SpeedUnit::Mph,
+                distance: DistanceUnit::Miles,
+                vertical_rate: VerticalRateUnit::FeetPerMinute,
+            },
+            UnitSystem::Aviation => Self {
+                altitude: AltitudeUnit::Feet,
+                speed: SpeedUnit::Knots,
+                distance: DistanceUnit::NauticalMiles,
+                vertical_rate: VerticalRateUnit::FeetPerMinute,
+            },
+        }
+    }
+
+    /// The configured preset with any per-quantity overrides applied.
+    pub fn from_config(ui: &UiConfig) -> Self {
+        let preset = Self::preset(ui.units);
+        Self {
+            altitude: ui.altitude_unit.unwrap_or(preset.altitude),
+            speed: ui.speed_unit.unwrap_or(preset.speed),
+            distance: ui.distance_unit.unwrap_or(preset.distance),
+            vertical_rate: ui.vertical_rate_unit.unwrap_or(preset.vertical_rate),
+        }
+    }
+
+    /// Formats an altitude given in metres, e.g. `"35000 ft"`.
+    pub fn altitude(&self, metres: f64) -> String {
+        match self.altitude {
+            AltitudeUnit::Metres => format!("{:.0} m", metres),
+            AltitudeUnit::Feet => format!("{:.0} ft", metres * FEET_PER_METRE),
+        }
+    }
+
+    /// Formats a ground speed given in m/s, e.g. `"450 kt"`.
+    pub fn speed(&self, metres_per_sec: f64) -> String {
+        match self.speed {
+            SpeedUnit::KilometresPerHour => format!("{:.0} km/h", metres_per_sec * 3.6),
+            SpeedUnit::Mph => format!("{:.0} mph", metres_per_sec * 3.6 / KM_PER_MILE),
+            SpeedUnit::Knots => format!("{:.0} kt", metres_per_sec * 3.6 / KM_PER_NAUTICAL_MILE),
+            SpeedUnit::MetresPerSecond => format!("{:.0} m/s", metres_per_sec),
+        }
+    }
+
+    /// Formats a distance given in kilometres, with one decimal below 10.
+    pub fn distance(&self, km: f64) -> String {
+        let (value, label) = match self.distance {
+            DistanceUnit::Kilometres => (km, "km"),
+            DistanceUnit::Miles => (km / KM_PER_MILE, "mi"),
+            DistanceUnit::NauticalMiles => (km / KM_PER_NAUTICAL_MILE, "NM"),
+        };
+        if value.abs() < 10.0 {
+            format!("{:.1} {}", value, label)
+        } else {
+            format!("{:.0} {}", value, label)
+        }
+    }
+
+    /// Formats a climb (+) or descent (-) rate given in m/s.
+    pub fn vertical_rate(&self, metres_per_sec: f64) -> String {
+        match self.vertical_rate {
+            VerticalRateUnit::MetresPerSecond => format!("{:+.1} m/s", metres_per_sec),
+            VerticalRateUnit::FeetPerMinute => {
+                format!("{:+.0} ft/min", metres_per_sec * FEET_PER_METRE * 60.0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_convert_from_si() {
+        let metric = Units::preset(UnitSystem::Metric);
+        assert_eq!(metric.altitude(10_668.0), "10668 m");
+        assert_eq!(metric.speed(250.0), "900 km/h");
+        assert_eq!(metric.distance(3.24), "3.2 km");
+        assert_eq!(metric.vertical_rate(-5.0), "-5.0 m/s");
+
+        let aviation = Units::preset(UnitSystem::Aviation);
+        assert_eq!(aviation.altitude(10_668.0), "35000 ft");
+        assert_eq!(aviation.speed(250.0), "486 kt");
+        assert_eq!(aviation.distance(92.6), "50 NM");
+        assert_eq!(aviation.vertical_rate(10.16), "+2000 ft/min");
+
+        let imperial = Units::preset(UnitSystem::Imperial);
+        assert_eq!(imperial.speed(250.0), "559 mph");
+        assert_eq!(imperial.distance(16.09344), "10 mi");
+    }
+
+    #[test]
+    fn test_overrides_from_config() {
+        let ui: UiConfig = toml::from_str(
+            r#"
+            default_view = "Dashboard"
+            units = "aviation"
+            distance_unit = "km"
+            speed_unit = "km/h"
+            "#,
+        )
+        .unwrap();
+        let units = Units::from_config(&ui);
+        assert_eq!(units.altitude, AltitudeUnit::Feet);
+        assert_eq!(units.vertical_rate, VerticalRateUnit::FeetPerMinute);
+        assert_eq!(units.distance, DistanceUnit::Kilometres);
+        assert_eq!(units.speed, SpeedUnit::KilometresPerHour);
+
+        // Older configs without unit settings stay metric.
+        let ui: UiConfig = toml::from_str(r#"default_view = "Spotter""#).unwrap();
+        assert_eq!(Units::from_config(&ui), Units::preset(UnitSystem::Metric));
+    }
+}